@@ -0,0 +1,15 @@
+fn main() {
+    println!("cargo:rerun-if-changed=proto");
+
+    // the service is generated from proto/ with protox so protoc isn't needed.
+    #[cfg(feature = "grpc")]
+    {
+        let descriptors = protox::compile(["rlarndg.proto"], ["proto"])
+            .expect("Couldn't compile the protobuf definitions");
+
+        tonic_build::configure()
+            .build_client(false)
+            .compile_fds(descriptors)
+            .expect("Couldn't generate the gRPC service");
+    }
+}
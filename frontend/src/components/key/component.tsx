@@ -14,7 +14,7 @@ export default function ApiKey({id, number}: ApiKeyProps): ReactElement {
 	const [sure, setSure] = useState(false);
 
 	function resetKey() {
-		fetch(`/api/keys/reset?id=${id}`, {method: "POST", credentials: "include"})
+		fetch(`/api/v1/keys/reset?id=${id}`, {method: "POST", credentials: "include"})
 			.then(r => r.text())
 			.then(setShownKey);
 
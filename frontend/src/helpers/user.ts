@@ -10,7 +10,7 @@ export interface OptionalUserProps {
 }
 
 export async function optionalUserMiddleware(): Promise<MResponse> {
-	const user = await fetch("/api/auth/user", {
+	const user = await fetch("/api/v1/auth/user", {
 		credentials: "include"
 	});
 
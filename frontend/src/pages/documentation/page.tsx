@@ -82,13 +82,13 @@ export default function Documentation({user}: OptionalUserProps): ReactElement {
 				or unless requested it returns an object with the <span className="md-highlight">red</span>
 				, <span className="md-highlight">green</span> and <span className="md-highlight">blue</span> fields
 				respectively. the<span className="md-highlight">format</span> query parameter
-				is allowed with hex or rgb values. Except <span className="md-highlight">hex</span> itself
-				returns a number fitting a 24 bits integer or more, not a css
-				formatted color.
+				is allowed with hex or rgb values, <span className="md-highlight">hex</span> returns
+				a css formatted color such as #ff8800, with a fourth byte when an
+				alpha channel is requested.
 			</p>
 			<Endpoint
 				method="GET"
-				url="/api/random/color"
+				url="/api/v1/random/color"
 				responseType="json-or-text"
 				query={[
 					{
@@ -116,7 +116,7 @@ export default function Documentation({user}: OptionalUserProps): ReactElement {
 			</p>
 			<Endpoint
 				method="GET"
-				url="/api/random/boolean"
+				url="/api/v1/random/boolean"
 				responseType="json-or-text"
 				headers={[
 					{
@@ -133,7 +133,7 @@ export default function Documentation({user}: OptionalUserProps): ReactElement {
 			</p>
 			<Endpoint
 				method="GET"
-				url="/api/random/signed"
+				url="/api/v1/random/signed"
 				responseType="json-or-text"
 				headers={[
 					{
@@ -151,7 +151,7 @@ export default function Documentation({user}: OptionalUserProps): ReactElement {
 			</p>
 			<Endpoint
 				method="GET"
-				url="/api/random/unsigned"
+				url="/api/v1/random/unsigned"
 				responseType="json-or-text"
 				headers={[
 					{
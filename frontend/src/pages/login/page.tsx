@@ -8,7 +8,7 @@ import Button from "../../components/button/component";
 import "./page.css";
 
 export async function loginMiddleware(): Promise<MResponse> {
-	const user = await fetch("/api/auth/user", {
+	const user = await fetch("/api/v1/auth/user", {
 		credentials: "include"
 	});
 
@@ -29,7 +29,7 @@ export default function Login(): ReactElement {
 			return;
 		}
 
-		const result = await fetch("/api/auth/login", {
+		const result = await fetch("/api/v1/auth/login", {
 			method: "POST",
 			body: JSON.stringify({
 				email: email.value,
@@ -42,7 +42,7 @@ export default function Login(): ReactElement {
 		});
 
 		if (result.status > 399) {
-			setError(await result.text());
+			setError((await result.json()).error.message);
 			return;
 		}
 
@@ -17,7 +17,7 @@ export default function Pricing({user}: OptionalUserProps): ReactElement {
 			return;
 
 		try {
-			location.assign(`/api/keys/checkout?amount=${input.valueAsNumber}`);
+			location.assign(`/api/v1/keys/checkout?amount=${input.valueAsNumber}`);
 		} catch {
 			setError(true);
 		}
@@ -15,7 +15,7 @@ export default function Register(): ReactElement {
 			return;
 		}
 
-		const result = await fetch("/api/auth/register", {
+		const result = await fetch("/api/v1/auth/register", {
 			method: "POST",
 			body: JSON.stringify({
 				email: email.value,
@@ -28,7 +28,7 @@ export default function Register(): ReactElement {
 		});
 
 		if (result.status > 399) {
-			setError(await result.text());
+			setError((await result.json()).error.message);
 			return;
 		}
 
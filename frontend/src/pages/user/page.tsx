@@ -13,7 +13,7 @@ interface UserPanelProps {
 }
 
 export async function userPannelMiddleware(): Promise<MResponse> {
-	const user = await fetch("/api/auth/user", {
+	const user = await fetch("/api/v1/auth/user", {
 		credentials: "include"
 	});
 
@@ -26,7 +26,7 @@ export default function UserPanel({user}: UserPanelProps): ReactElement {
 	const [keys, setKeys] = useState<number[] | undefined>();
 
 	useEffect(() => {
-		fetch("/api/keys/user", {
+		fetch("/api/v1/keys/user", {
 			credentials: "include"
 		})
 			.then(r => r.json())
@@ -8,7 +8,6 @@ export default defineConfig({
 		proxy: {
 			"/api": {
 				target: "http://localhost:5174",
-				rewrite: (path) => path.replace(/^\/api/, ""),
 				changeOrigin: false
 			}
 		}
@@ -0,0 +1,8 @@
+
+CREATE TABLE decks (
+	id SERIAL PRIMARY KEY,
+	key_id INTEGER REFERENCES keys(id) ON DELETE CASCADE NOT NULL,
+	cards TEXT[] NOT NULL,
+	remaining TEXT[] NOT NULL,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
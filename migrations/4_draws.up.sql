@@ -0,0 +1,14 @@
+
+CREATE TABLE draws (
+	id SERIAL PRIMARY KEY,
+	key_id INTEGER REFERENCES keys(id) ON DELETE CASCADE NOT NULL,
+	participants TEXT[] NOT NULL,
+	winners_count INTEGER NOT NULL,
+	commitment VARCHAR(64) NOT NULL,
+	draw_at TIMESTAMPTZ NOT NULL,
+	seed VARCHAR(64),
+	segment_digests TEXT[],
+	winners TEXT[],
+	drawn_at TIMESTAMPTZ,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
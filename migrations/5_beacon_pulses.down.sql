@@ -0,0 +1,2 @@
+
+DROP TABLE beacon_pulses;
@@ -0,0 +1,12 @@
+
+CREATE TABLE beacon_pulses (
+	pulse_index BIGINT PRIMARY KEY,
+	time_stamp TIMESTAMPTZ NOT NULL,
+	period INTEGER NOT NULL,
+	local_random_value VARCHAR(128) NOT NULL,
+	previous_output_value VARCHAR(128) NOT NULL,
+	segment_digests TEXT[] NOT NULL,
+	output_value VARCHAR(128) NOT NULL
+);
+
+CREATE INDEX beacon_pulses_time_stamp ON beacon_pulses (time_stamp);
@@ -0,0 +1,12 @@
+
+CREATE TABLE receipts (
+	id VARCHAR(32) PRIMARY KEY,
+	key_id INTEGER REFERENCES keys(id) ON DELETE CASCADE,
+	endpoint VARCHAR(255) NOT NULL,
+	parameters TEXT NOT NULL,
+	value JSONB NOT NULL,
+	segment_digests TEXT[] NOT NULL,
+	issued_at TIMESTAMPTZ NOT NULL
+);
+
+CREATE INDEX receipts_issued_at ON receipts (issued_at);
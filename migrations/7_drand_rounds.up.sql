@@ -0,0 +1,9 @@
+
+CREATE TABLE drand_rounds (
+	round BIGINT PRIMARY KEY,
+	signature VARCHAR(128) NOT NULL,
+	previous_signature VARCHAR(128) NOT NULL,
+	local_random_value VARCHAR(64) NOT NULL,
+	segment_digests TEXT[] NOT NULL,
+	created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
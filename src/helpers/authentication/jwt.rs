@@ -1,11 +1,17 @@
 use std::sync::OnceLock;
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
+use crate::helpers::misc::config::config;
 
 pub static JWT_HASH: OnceLock<String> = OnceLock::new();
 
 #[inline]
 pub fn get_jwt_hash() -> &'static [u8] {
+    // without a configured secret sessions don't survive a restart.
     JWT_HASH.get_or_init(|| {
+        if let Some(secret) = &config().secrets.jwt {
+            return secret.clone();
+        }
+
         thread_rng()
             .sample_iter(&Alphanumeric)
             .take(512)
@@ -0,0 +1,129 @@
+use std::{fs::{read_to_string, OpenOptions}, io::{Error as IoError, ErrorKind, Write}, os::unix::fs::OpenOptionsExt, path::Path, sync::{atomic::{AtomicU64, Ordering}, LazyLock, OnceLock}};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey, SECRET_KEY_LENGTH};
+use hex::FromHexError;
+use log::info;
+use rand::{thread_rng, RngCore};
+use serde::Serialize;
+use serde_json::{to_string, to_value, Error as JsonError};
+use thiserror::Error;
+use time::OffsetDateTime;
+use crate::helpers::misc::config::config;
+
+static SIGNING_KEY: OnceLock<Option<SigningKey>> = OnceLock::new();
+
+// serials start at the boot time in microseconds so they keep
+// increasing across restarts.
+static SERIAL: LazyLock<AtomicU64> = LazyLock::new(|| AtomicU64::new(
+    (OffsetDateTime::now_utc().unix_timestamp_nanos() / 1000) as u64
+));
+
+#[derive(Debug, Error)]
+pub enum SigningError {
+    #[error("{0:#}")]
+    Io(#[from] IoError),
+
+    #[error("{0:#}")]
+    Hex(#[from] FromHexError),
+
+    #[error("{0:#}")]
+    Json(#[from] JsonError),
+
+    #[error("The signing key must be {SECRET_KEY_LENGTH} bytes long.")]
+    KeyLength
+}
+
+// a new key is only readable by its owner, and an existing one is never
+// overwritten even if another process created it in the meantime.
+fn load_signing_key(path: &Path) -> Result<SigningKey, SigningError> {
+    let created = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(path);
+
+    match created {
+        Ok(mut file) => {
+            let mut secret = [0u8; SECRET_KEY_LENGTH];
+
+            thread_rng().fill_bytes(&mut secret);
+            file.write_all(hex::encode(secret).as_bytes())?;
+
+            info!("Generated a new signing key at {}.", path.display());
+
+            return Ok(SigningKey::from_bytes(&secret));
+        },
+        Err(error) if error.kind() == ErrorKind::AlreadyExists => {},
+        Err(error) => return Err(error.into())
+    }
+
+    let secret = hex::decode(read_to_string(path)?.trim())?;
+
+    Ok(SigningKey::from_bytes(
+        &secret
+            .try_into()
+            .map_err(|_| SigningError::KeyLength)?
+    ))
+}
+
+pub fn init_signing_key() -> Result<(), SigningError> {
+    let key = config()
+        .signing
+        .key
+        .as_deref()
+        .map(load_signing_key)
+        .transpose()?;
+
+    SIGNING_KEY.get_or_init(|| key);
+
+    Ok(())
+}
+
+pub fn verifying_key() -> Option<VerifyingKey> {
+    SIGNING_KEY
+        .get()?
+        .as_ref()
+        .map(SigningKey::verifying_key)
+}
+
+pub fn next_serial() -> u64 {
+    SERIAL.fetch_add(1, Ordering::Relaxed)
+}
+
+// the canonical form is the compact json of the payload with the object keys sorted.
+pub fn canonical<T: Serialize>(payload: &T) -> Result<String, JsonError> {
+    to_string(&to_value(payload)?)
+}
+
+pub fn sign<T: Serialize>(payload: &T) -> Result<Option<String>, SigningError> {
+    let Some(Some(key)) = SIGNING_KEY.get() else {
+        return Ok(None);
+    };
+
+    Ok(Some(hex::encode(
+        key.sign(canonical(payload)?.as_bytes())
+            .to_bytes()
+    )))
+}
+
+pub fn sign_raw(message: &[u8]) -> Option<[u8; 64]> {
+    let Some(Some(key)) = SIGNING_KEY.get() else {
+        return None;
+    };
+
+    Some(key.sign(message).to_bytes())
+}
+
+pub fn verify<T: Serialize>(payload: &T, signature: &str) -> Result<bool, SigningError> {
+    let Some(key) = verifying_key() else {
+        return Ok(false);
+    };
+
+    let Ok(signature) = Signature::from_slice(&hex::decode(signature)?) else {
+        return Ok(false);
+    };
+
+    Ok(
+        key.verify(canonical(payload)?.as_bytes(), &signature)
+            .is_ok()
+    )
+}
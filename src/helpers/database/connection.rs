@@ -6,6 +6,7 @@ use sqlx::{
 use std::path::Path;
 use std::sync::OnceLock;
 use thiserror::Error;
+use crate::helpers::misc::config::config;
 
 static CONNECTION: OnceLock<Pool<Postgres>> = OnceLock::new();
 
@@ -23,9 +24,11 @@ pub async fn get_db_connection<'r>() -> Result<&'r Pool<Postgres>, DbConnectionE
         return Ok(connection);
     }
 
+    let database = &config().database;
+
     let pool = PgPoolOptions::new()
-        .max_connections(5)
-        .connect(&lc!(env!("DATABASE_URL")))
+        .max_connections(database.max_connections)
+        .connect(&database.url)
         .await?;
 
     Migrator::new(Path::new("./migrations"))
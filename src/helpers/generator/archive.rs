@@ -0,0 +1,151 @@
+use std::{io::{Error as IoError, ErrorKind}, path::PathBuf, sync::OnceLock, time::{Duration, SystemTime}};
+use actix_web::web::Bytes;
+use log::{info, warn};
+use rand::{thread_rng, Rng};
+use tokio::{fs::{create_dir_all, metadata, read, read_dir, remove_file, rename, write}, time::interval};
+use crate::helpers::misc::config::config;
+
+pub struct ArchiveSettings {
+    directory: PathBuf,
+    retention: Duration,
+    max_bytes: u64
+}
+
+static SETTINGS: OnceLock<Option<ArchiveSettings>> = OnceLock::new();
+
+// the archive is only enabled when its directory is configured.
+pub fn archive_settings() -> Option<&'static ArchiveSettings> {
+    SETTINGS
+        .get_or_init(|| {
+            let archive = config().archive.as_ref()?;
+
+            Some(ArchiveSettings {
+                directory: archive.directory.clone(),
+                retention: Duration::from_secs(archive.retention_days * 86400),
+                max_bytes: archive.max_bytes
+            })
+        })
+        .as_ref()
+}
+
+fn is_digest(digest: &str) -> bool {
+    digest.len() == 64 && digest.chars().all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase())
+}
+
+impl ArchiveSettings {
+    // segments are sharded by the first two characters of their digest.
+    fn segment_path(&self, digest: &str) -> PathBuf {
+        self.directory
+            .join(&digest[..2])
+            .join(digest)
+    }
+
+    pub async fn store(&self, digest: &str, bytes: &Bytes) -> Result<(), IoError> {
+        let path = self.segment_path(digest);
+
+        if metadata(&path).await.is_ok() {
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            create_dir_all(parent).await?;
+        }
+
+        // the segment only appears under its digest once it's fully written,
+        // the suffix keeps concurrent stores of the same digest apart.
+        let temporary = path.with_extension(format!("{:016x}.tmp", thread_rng().gen::<u64>()));
+
+        if let Err(error) = write(&temporary, bytes).await {
+            let _ = remove_file(&temporary).await;
+            return Err(error);
+        }
+
+        rename(temporary, path).await
+    }
+
+    pub async fn load(&self, digest: &str) -> Result<Option<Vec<u8>>, IoError> {
+        if !is_digest(digest) {
+            return Ok(None);
+        }
+
+        match read(self.segment_path(digest)).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(error)
+        }
+    }
+
+    async fn cleanup(&self) -> Result<(), IoError> {
+        let now = SystemTime::now();
+        let mut segments = Vec::new();
+        let mut shards = read_dir(&self.directory).await?;
+
+        while let Some(shard) = shards.next_entry().await? {
+            if !shard.file_type().await?.is_dir() {
+                continue;
+            }
+
+            let mut files = read_dir(shard.path()).await?;
+
+            while let Some(file) = files.next_entry().await? {
+                let metadata = file.metadata().await?;
+                let modified = metadata.modified()?;
+
+                // temporary files of stores in progress only go once they're expired.
+                if now.duration_since(modified).unwrap_or_default() > self.retention {
+                    remove_file(file.path()).await?;
+                } else if is_digest(&file.file_name().to_string_lossy()) {
+                    segments.push((modified, metadata.len(), file.path()));
+                }
+            }
+        }
+
+        let mut total = segments
+            .iter()
+            .map(|(_, size, _)| size)
+            .sum::<u64>();
+
+        segments.sort();
+
+        for (_, size, path) in segments {
+            if total <= self.max_bytes {
+                break;
+            }
+
+            remove_file(path).await?;
+            total -= size;
+        }
+
+        Ok(())
+    }
+}
+
+// segments are written in the background so captures don't wait on the disk.
+pub fn archive_segment(digest: &str, bytes: &Bytes) {
+    let Some(settings) = archive_settings() else {
+        return;
+    };
+
+    let digest = digest.to_string();
+    let bytes = bytes.clone();
+
+    tokio::spawn(async move {
+        if let Err(error) = settings.store(&digest, &bytes).await {
+            warn!("Couldn't archive segment {digest}, an error occurred: {error:#}");
+        }
+    });
+}
+
+pub async fn run_archive_cleanup(settings: &'static ArchiveSettings) {
+    let mut ticker = interval(Duration::from_secs(300));
+
+    info!("Archiving segments at {}.", settings.directory.display());
+
+    loop {
+        ticker.tick().await;
+
+        if let Err(error) = settings.cleanup().await {
+            warn!("Couldn't clean up the segment archive, an error occurred: {error:#}");
+        }
+    }
+}
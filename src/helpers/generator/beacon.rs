@@ -0,0 +1,85 @@
+use std::time::Duration;
+use log::{info, warn};
+use thiserror::Error;
+use time::{error::Format as FormatError, format_description::well_known::Rfc3339, OffsetDateTime};
+use tokio::time::{interval, MissedTickBehavior};
+use crate::{helpers::misc::{config::config, digest::sha256_hex}, models::pulse::{output_value, Pulse, PulseError, GENESIS_OUTPUT_VALUE}};
+use super::{frame::{frame_bytes_from_source, CaptureError}, random::get_unsigned};
+
+#[derive(Debug, Error)]
+pub enum BeaconError {
+    #[error("{0:#}")]
+    Capture(#[from] CaptureError<'static>),
+
+    #[error("{0:#}")]
+    Pulse(#[from] PulseError),
+
+    #[error("{0:#}")]
+    Format(#[from] FormatError)
+}
+
+pub fn beacon_period() -> Option<Duration> {
+    config()
+        .beacon
+        .interval
+        .map(Duration::from_secs)
+}
+
+pub async fn emit_pulse(period: Duration) -> Result<Pulse, BeaconError> {
+    let frame = frame_bytes_from_source().await?;
+    let previous = Pulse::latest().await?;
+
+    let local_random_value = hex::encode(
+        (0..16)
+            .flat_map(|_| get_unsigned(&frame).to_be_bytes())
+            .collect::<Vec<_>>()
+    );
+
+    let (pulse_index, previous_output_value) = match previous {
+        Some(pulse) => (pulse.pulse_index + 1, pulse.output_value),
+        None => (1, GENESIS_OUTPUT_VALUE.to_string())
+    };
+
+    let time_stamp = OffsetDateTime::now_utc()
+        .replace_nanosecond(0)
+        .unwrap_or_else(|_| OffsetDateTime::now_utc());
+
+    let segment_digests = vec![sha256_hex(&frame)];
+
+    let pulse = Pulse {
+        pulse_index,
+        output_value: output_value(
+            pulse_index,
+            &time_stamp.format(&Rfc3339)?,
+            &local_random_value,
+            &previous_output_value,
+            &segment_digests
+        ),
+        time_stamp,
+        period: period.as_millis() as i32,
+        local_random_value,
+        previous_output_value,
+        segment_digests
+    };
+
+    pulse.save().await?;
+
+    Ok(pulse)
+}
+
+pub async fn run_beacon(period: Duration) {
+    let mut ticker = interval(period);
+
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    info!("Emitting beacon pulses every {} seconds.", period.as_secs());
+
+    loop {
+        ticker.tick().await;
+
+        match emit_pulse(period).await {
+            Ok(pulse) => info!("Emitted beacon pulse {}.", pulse.pulse_index),
+            Err(error) => warn!("Couldn't emit a beacon pulse, an error occurred: {error:#}")
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::{sync::{atomic::{AtomicBool, Ordering}, Arc}, time::Duration};
+use actix_web::web::Bytes;
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use thiserror::Error;
+use tokio::{task::{spawn_blocking, JoinError}, time::timeout};
+use super::random::get_unsigned;
+
+const SMALL_PRIMES: [u32; 54] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+    101, 103, 107, 109, 113, 127, 131, 137, 139, 149, 151, 157, 163, 167, 173, 179, 181, 191, 193,
+    197, 199, 211, 223, 227, 229, 233, 239, 241, 251
+];
+
+const MILLER_RABIN_ROUNDS: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum BigIntError {
+    #[error("Invalid bit size {bits}, expected a value between {min} and {max} for this key.")]
+    BitsOutOfRange { bits: u64, min: u64, max: u64 },
+
+    #[error("Invalid format `{0}`, expected either dec or hex.")]
+    InvalidFormat(String),
+
+    #[error("The generation took longer than {0} seconds and was cancelled.")]
+    Timeout(u64),
+
+    #[error("The generation was cancelled.")]
+    Cancelled,
+
+    #[error("{0:#}")]
+    Join(#[from] JoinError)
+}
+
+#[derive(Clone, Copy)]
+pub enum BigIntFormat {
+    Decimal,
+    Hexadecimal
+}
+
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+impl BigIntFormat {
+    pub fn parse(value: Option<&str>) -> Result<Self, BigIntError> {
+        match value {
+            None | Some("dec") => Ok(Self::Decimal),
+            Some("hex") => Ok(Self::Hexadecimal),
+            Some(value) => Err(BigIntError::InvalidFormat(value.to_string()))
+        }
+    }
+
+    pub fn format(&self, value: &BigUint) -> String {
+        match self {
+            Self::Decimal => value.to_str_radix(10),
+            Self::Hexadecimal => value.to_str_radix(16)
+        }
+    }
+}
+
+pub fn check_bits(bits: u64, min: u64, max: u64) -> Result<u64, BigIntError> {
+    match (min..=max).contains(&bits) {
+        true => Ok(bits),
+        false => Err(BigIntError::BitsOutOfRange { bits, min, max })
+    }
+}
+
+pub fn random_bits(frame: &Bytes, bits: u64) -> BigUint {
+    let words = (0..bits.div_ceil(32))
+        .map(|_| get_unsigned(frame))
+        .collect::<Vec<_>>();
+
+    let mut value = BigUint::from_slice(&words);
+
+    // the extra bits of the last word are cleared so the value is uniform in [0, 2^bits).
+    for bit in bits..words.len() as u64 * 32 {
+        value.set_bit(bit, false);
+    }
+
+    value
+}
+
+fn random_below(frame: &Bytes, bound: &BigUint) -> BigUint {
+    loop {
+        let value = random_bits(frame, bound.bits());
+
+        if &value < bound {
+            return value;
+        }
+    }
+}
+
+fn is_probable_prime(frame: &Bytes, candidate: &BigUint, cancelled: &AtomicBool) -> Option<bool> {
+    for prime in SMALL_PRIMES {
+        if *candidate == BigUint::from(prime) {
+            return Some(true);
+        }
+
+        if (candidate % prime).is_zero() {
+            return Some(false);
+        }
+    }
+
+    let one = BigUint::one();
+    let two = BigUint::from(2u32);
+    let predecessor = candidate - &one;
+    let shift = predecessor.trailing_zeros().unwrap_or(0);
+    let odd = &predecessor >> shift;
+
+    'witness: for _ in 0..MILLER_RABIN_ROUNDS {
+        if cancelled.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        // witnesses are taken from the same entropy as the candidate, in [2, n - 2].
+        let witness = random_below(frame, &(candidate - 3u32)) + &two;
+        let mut value = witness.modpow(&odd, candidate);
+
+        if value == one || value == predecessor {
+            continue;
+        }
+
+        for _ in 1..shift {
+            value = value.modpow(&two, candidate);
+
+            if value == predecessor {
+                continue 'witness;
+            }
+        }
+
+        return Some(false);
+    }
+
+    Some(true)
+}
+
+pub fn random_prime(frame: &Bytes, bits: u64, cancelled: &AtomicBool) -> Option<BigUint> {
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let mut candidate = random_bits(frame, bits);
+
+        candidate.set_bit(bits - 1, true);
+        candidate.set_bit(0, true);
+
+        if is_probable_prime(frame, &candidate, cancelled)? {
+            return Some(candidate);
+        }
+    }
+}
+
+// runs the job on the blocking pool so the actix workers keep serving other
+// requests, the job is told to stop if the request is dropped or times out.
+pub async fn cancellable<T, F>(job: F, limit: Duration) -> Result<T, BigIntError>
+where
+    T: Send + 'static,
+    F: FnOnce(&AtomicBool) -> Option<T> + Send + 'static
+{
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let _guard = CancelOnDrop(cancelled.clone());
+
+    let task = spawn_blocking(move || job(&cancelled));
+
+    match timeout(limit, task).await {
+        Ok(result) => result?.ok_or(BigIntError::Cancelled),
+        Err(_) => Err(BigIntError::Timeout(limit.as_secs()))
+    }
+}
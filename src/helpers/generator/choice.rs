@@ -0,0 +1,167 @@
+use actix_web::web::Bytes;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+use utoipa::ToSchema;
+use super::random::get_ranged_wide;
+
+const MAX_ITEMS: usize = 10_000;
+const MAX_PICKS: usize = 10_000;
+
+#[derive(Debug, Error)]
+pub enum ChoiceError {
+    #[error("Between 1 and {MAX_ITEMS} items must be provided.")]
+    ItemCount,
+
+    #[error("Between 1 and {MAX_PICKS} picks can be requested.")]
+    PickCount,
+
+    #[error("The weights must add up to a value between 1 and 2^64 - 1.")]
+    TotalWeight,
+
+    #[error("Only {0} items have a weight, they can't be picked {1} times without replacement.")]
+    NotEnoughItems(usize, usize)
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct WeightedItem {
+    item: Value,
+    weight: u64
+}
+
+// a fenwick tree over the weights, so removing a picked item and finding
+// the next one both stay logarithmic in the number of items.
+struct WeightTree {
+    tree: Vec<u64>
+}
+
+impl WeightTree {
+    fn new(weights: &[u64]) -> Self {
+        let mut tree = vec![0; weights.len() + 1];
+
+        for (index, weight) in weights.iter().enumerate() {
+            let node = index + 1;
+            tree[node] += weight;
+
+            let parent = node + (node & node.wrapping_neg());
+
+            if parent < tree.len() {
+                tree[parent] += tree[node];
+            }
+        }
+
+        Self { tree }
+    }
+
+    // the first index whose cumulative weight goes over the target.
+    fn find(&self, mut target: u64) -> Option<usize> {
+        let length = self.tree.len() - 1;
+        let mut position = 0;
+        let mut step = length.checked_ilog2().map_or(0, |log| 1 << log);
+
+        while step > 0 {
+            if position + step <= length && self.tree[position + step] <= target {
+                position += step;
+                target -= self.tree[position];
+            }
+
+            step >>= 1;
+        }
+
+        (position < length).then_some(position)
+    }
+
+    fn remove(&mut self, index: usize, weight: u64) {
+        let mut node = index + 1;
+
+        while node < self.tree.len() {
+            self.tree[node] -= weight;
+            node += node & node.wrapping_neg();
+        }
+    }
+}
+
+// checks the draw can be made and returns the total weight, callers charging
+// for a draw use it to validate the request first.
+pub fn check_choice(items: &[WeightedItem], count: usize, replacement: bool) -> Result<u64, ChoiceError> {
+    if !(1..=MAX_ITEMS).contains(&items.len()) {
+        return Err(ChoiceError::ItemCount);
+    }
+
+    if !(1..=MAX_PICKS).contains(&count) {
+        return Err(ChoiceError::PickCount);
+    }
+
+    let total = items
+        .iter()
+        .try_fold(0u64, |total, item| total.checked_add(item.weight))
+        .filter(|total| *total > 0)
+        .ok_or(ChoiceError::TotalWeight)?;
+
+    let weighted = items
+        .iter()
+        .filter(|item| item.weight > 0)
+        .count();
+
+    if !replacement && count > weighted {
+        return Err(ChoiceError::NotEnoughItems(weighted, count));
+    }
+
+    Ok(total)
+}
+
+// every index is as likely as its weight over the total, the weights are
+// integers so there's no floating point bias involved.
+pub fn choose(frame: &Bytes, items: Vec<WeightedItem>, count: usize, replacement: bool) -> Result<Vec<Value>, ChoiceError> {
+    let mut total = check_choice(&items, count, replacement)?;
+
+    let weights = items
+        .iter()
+        .map(|item| item.weight)
+        .collect::<Vec<_>>();
+
+    let indexes = match replacement {
+        true => {
+            let cumulative = weights
+                .iter()
+                .scan(0u64, |sum, weight| {
+                    *sum += weight;
+                    Some(*sum)
+                })
+                .collect::<Vec<_>>();
+
+            (0..count)
+                .map(|_| {
+                    let target = get_ranged_wide(frame, total);
+                    let index = cumulative.partition_point(|sum| *sum <= target);
+
+                    (index < cumulative.len())
+                        .then_some(index)
+                        .ok_or(ChoiceError::TotalWeight)
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        },
+        // without replacement the picked item stops taking part in the draw.
+        false => {
+            let mut tree = WeightTree::new(&weights);
+            let mut indexes = Vec::with_capacity(count);
+
+            for _ in 0..count {
+                let index = tree
+                    .find(get_ranged_wide(frame, total))
+                    .ok_or(ChoiceError::TotalWeight)?;
+
+                tree.remove(index, weights[index]);
+                total -= weights[index];
+                indexes.push(index);
+            }
+
+            indexes
+        }
+    };
+
+    Ok(indexes
+        .into_iter()
+        .map(|index| items[index].item.clone())
+        .collect())
+}
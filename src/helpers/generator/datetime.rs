@@ -0,0 +1,165 @@
+use actix_web::web::Bytes;
+use serde::Serialize;
+use thiserror::Error;
+use time::{error::{Format as FormatError, InvalidFormatDescription}, format_description::{parse_owned, well_known::Rfc3339, OwnedFormatItem}, macros::format_description, Date, Duration, OffsetDateTime, UtcOffset};
+use utoipa::ToSchema;
+use super::random::get_ranged_wide;
+
+#[derive(Debug, Error)]
+pub enum DateTimeError {
+    #[error("Invalid `{0}` bound, expected an RFC 3339 timestamp such as 2024-01-01T00:00:00Z.")]
+    InvalidInstant(&'static str),
+
+    #[error("Invalid `{0}` bound, expected a date such as 2024-01-01.")]
+    InvalidDate(&'static str),
+
+    #[error("Invalid offset `{0}`, expected an offset such as +02:00 or Z.")]
+    InvalidOffset(String),
+
+    #[error("The `from` bound can't be after the `to` bound.")]
+    InvertedRange,
+
+    #[error("The range can't be shown at offset {0}, it would leave the supported years.")]
+    UnsupportedOffset(UtcOffset),
+
+    #[error("Invalid format description: {0:#}")]
+    InvalidFormat(#[from] InvalidFormatDescription),
+
+    #[error("The value couldn't be formatted: {0:#}")]
+    Format(#[from] FormatError)
+}
+
+pub enum DateTimeFormat {
+    Unix,
+    Rfc3339,
+    Custom(OwnedFormatItem)
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum FormattedDateTime {
+    Unix(i64),
+    Text(String)
+}
+
+pub struct DateTimeRange {
+    from: OffsetDateTime,
+    to: OffsetDateTime,
+    date_only: bool,
+    offset: UtcOffset
+}
+
+fn parse_instant(value: &str, bound: &'static str, date_only: bool) -> Result<OffsetDateTime, DateTimeError> {
+    if date_only {
+        return Date::parse(value, format_description!("[year]-[month]-[day]"))
+            .map(|date| date.midnight().assume_utc())
+            .map_err(|_| DateTimeError::InvalidDate(bound));
+    }
+
+    OffsetDateTime::parse(value, &Rfc3339)
+        .map_err(|_| DateTimeError::InvalidInstant(bound))
+}
+
+fn parse_offset(value: &str) -> Result<UtcOffset, DateTimeError> {
+    if value == "Z" || value == "z" {
+        return Ok(UtcOffset::UTC);
+    }
+
+    UtcOffset::parse(value, format_description!("[offset_hour sign:mandatory]:[offset_minute]"))
+        .map_err(|_| DateTimeError::InvalidOffset(value.to_string()))
+}
+
+impl DateTimeFormat {
+    pub fn parse(value: Option<&str>) -> Result<Self, DateTimeError> {
+        match value {
+            None | Some("rfc3339") => Ok(Self::Rfc3339),
+            Some("unix") => Ok(Self::Unix),
+            Some(description) => Ok(Self::Custom(parse_owned::<2>(description)?))
+        }
+    }
+}
+
+impl DateTimeRange {
+    pub fn new(from: Option<&str>, to: Option<&str>, offset: Option<&str>, date_only: bool)
+        -> Result<Self, DateTimeError> {
+
+        let from = match from {
+            Some(from) => parse_instant(from, "from", date_only)?,
+            None => OffsetDateTime::UNIX_EPOCH
+        };
+
+        let to = match to {
+            Some(to) => parse_instant(to, "to", date_only)?,
+            None if date_only => OffsetDateTime::now_utc()
+                .date()
+                .midnight()
+                .assume_utc(),
+            None => OffsetDateTime::now_utc()
+        };
+
+        if from > to {
+            return Err(DateTimeError::InvertedRange);
+        }
+
+        let offset = offset
+            .map(parse_offset)
+            .transpose()?
+            .unwrap_or(UtcOffset::UTC);
+
+        // every random instant lies between the bounds, so they're the only ones to check.
+        if !date_only && (from.checked_to_offset(offset).is_none() || to.checked_to_offset(offset).is_none()) {
+            return Err(DateTimeError::UnsupportedOffset(offset));
+        }
+
+        Ok(Self {
+            from,
+            to,
+            date_only,
+            offset
+        })
+    }
+
+    pub fn random(&self, frame: &Bytes) -> OffsetDateTime {
+        // both bounds are inclusive, dates are picked as whole days and
+        // instants with a microsecond resolution.
+        let span = self.to - self.from;
+
+        match self.date_only {
+            true => self.from + Duration::days(
+                get_ranged_wide(frame, span.whole_days() as u64 + 1) as i64
+            ),
+            false => self.from + Duration::microseconds(
+                get_ranged_wide(frame, span.whole_microseconds() as u64 + 1) as i64
+            )
+        }
+    }
+
+    // formatting both bounds catches descriptions the values can't be written with
+    // before anything is generated.
+    pub fn check_format(&self, format: &DateTimeFormat) -> Result<(), DateTimeError> {
+        self.format(self.from, format)?;
+        self.format(self.to, format)?;
+
+        Ok(())
+    }
+
+    pub fn format(&self, instant: OffsetDateTime, format: &DateTimeFormat)
+        -> Result<FormattedDateTime, DateTimeError> {
+
+        let instant = match self.date_only {
+            true => instant,
+            false => instant.to_offset(self.offset)
+        };
+
+        Ok(match (format, self.date_only) {
+            (DateTimeFormat::Unix, _) => FormattedDateTime::Unix(instant.unix_timestamp()),
+            (DateTimeFormat::Rfc3339, true) => FormattedDateTime::Text(
+                instant
+                    .date()
+                    .format(format_description!("[year]-[month]-[day]"))?
+            ),
+            (DateTimeFormat::Rfc3339, false) => FormattedDateTime::Text(instant.format(&Rfc3339)?),
+            (DateTimeFormat::Custom(description), _) => FormattedDateTime::Text(instant.format(description)?)
+        })
+    }
+}
@@ -0,0 +1,400 @@
+use std::{iter::Peekable, str::{CharIndices, FromStr}};
+use actix_web::web::Bytes;
+use serde::Serialize;
+use thiserror::Error;
+use utoipa::ToSchema;
+use super::random::get_ranged;
+
+const MAX_TERMS: usize = 32;
+const MAX_DICE: u32 = 1000;
+const MAX_SIDES: u32 = 1_000_000;
+const MAX_CONSTANT: u32 = 1_000_000;
+const MAX_EXPLOSIONS: u32 = 100;
+
+#[derive(Debug, Error, Serialize)]
+#[serde(tag = "error", rename_all = "snake_case")]
+pub enum DiceError {
+    #[error("Unexpected token `{token}` at position {position}.")]
+    UnexpectedToken { token: String, position: usize },
+
+    #[error("The expression ended unexpectedly at position {position}, expected {expected}.")]
+    UnexpectedEnd { expected: &'static str, position: usize },
+
+    #[error("The value `{token}` at position {position} exceeds the limit of {limit}.")]
+    LimitExceeded { token: String, position: usize, limit: u32 },
+
+    #[error("The dice `{token}` at position {position} can't be rolled, {reason}.")]
+    InvalidDice { token: String, position: usize, reason: &'static str },
+
+    #[error("The expression has more than {limit} terms.")]
+    TooManyTerms { limit: usize }
+}
+
+#[derive(Clone, Copy)]
+enum Keep {
+    Highest(u32),
+    Lowest(u32),
+    DropHighest(u32),
+    DropLowest(u32)
+}
+
+enum Term {
+    Dice {
+        count: u32,
+        sides: u32,
+        explode: bool,
+        keep: Option<Keep>
+    },
+    Constant(u32)
+}
+
+struct SignedTerm {
+    negative: bool,
+    text: String,
+    term: Term
+}
+
+pub struct DiceExpression {
+    terms: Vec<SignedTerm>
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DieRoll {
+    value: u32,
+    kept: bool,
+    exploded: bool
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TermRoll {
+    term: String,
+    sign: i8,
+    dice: Vec<DieRoll>,
+    subtotal: i64
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DiceRoll {
+    expression: String,
+    terms: Vec<TermRoll>,
+    total: i64
+}
+
+struct Parser<'s> {
+    chars: Peekable<CharIndices<'s>>,
+    length: usize
+}
+
+impl<'s> Parser<'s> {
+    fn new(input: &'s str) -> Self {
+        Self {
+            chars: input.char_indices().peekable(),
+            length: input.len()
+        }
+    }
+
+    fn position(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map_or(self.length, |(position, _)| *position)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        self.chars
+            .next_if(|(_, c)| c.eq_ignore_ascii_case(&expected))
+            .is_some()
+    }
+
+    fn unexpected(&mut self, expected: &'static str) -> DiceError {
+        match self.chars.peek() {
+            Some((position, token)) => DiceError::UnexpectedToken {
+                token: token.to_string(),
+                position: *position
+            },
+            None => DiceError::UnexpectedEnd {
+                expected,
+                position: self.length
+            }
+        }
+    }
+
+    fn number(&mut self, limit: u32) -> Result<Option<u32>, DiceError> {
+        let position = self.position();
+        let mut digits = String::new();
+
+        while let Some((_, digit)) = self.chars.next_if(|(_, c)| c.is_ascii_digit()) {
+            digits.push(digit);
+        }
+
+        if digits.is_empty() {
+            return Ok(None);
+        }
+
+        match digits.parse::<u32>() {
+            Ok(value) if value <= limit => Ok(Some(value)),
+            _ => Err(DiceError::LimitExceeded { token: digits, position, limit })
+        }
+    }
+
+    fn required_number(&mut self, limit: u32, expected: &'static str) -> Result<u32, DiceError> {
+        match self.number(limit)? {
+            Some(value) => Ok(value),
+            None => Err(self.unexpected(expected))
+        }
+    }
+
+    fn keep(&mut self) -> Result<Option<Keep>, DiceError> {
+        let keep = if self.eat('k') {
+            if self.eat('l') {
+                Keep::Lowest
+            } else {
+                self.eat('h');
+                Keep::Highest
+            }
+        } else if self.eat('d') {
+            if self.eat('h') {
+                Keep::DropHighest
+            } else if self.eat('l') {
+                Keep::DropLowest
+            } else {
+                return Err(self.unexpected("`h` or `l`"));
+            }
+        } else {
+            return Ok(None);
+        };
+
+        Ok(Some(keep(self.required_number(MAX_DICE, "an amount of dice")?)))
+    }
+
+    fn term(&mut self) -> Result<Term, DiceError> {
+        let position = self.position();
+        let number = self.number(MAX_CONSTANT)?;
+
+        if !self.eat('d') {
+            return match number {
+                Some(value) => Ok(Term::Constant(value)),
+                None => Err(self.unexpected("a number or dice"))
+            };
+        }
+
+        let count = number.unwrap_or(1);
+
+        if count > MAX_DICE {
+            return Err(DiceError::LimitExceeded {
+                token: count.to_string(),
+                position,
+                limit: MAX_DICE
+            });
+        }
+
+        let sides = if self.eat('%') {
+            100
+        } else {
+            self.required_number(MAX_SIDES, "the amount of sides")?
+        };
+
+        if count == 0 || sides == 0 {
+            return Err(DiceError::InvalidDice {
+                token: format!("{count}d{sides}"),
+                position,
+                reason: "it has no dice or no sides"
+            });
+        }
+
+        let mut explode = false;
+        let mut keep = None;
+
+        loop {
+            if !explode && self.eat('!') {
+                if sides == 1 {
+                    return Err(DiceError::InvalidDice {
+                        token: format!("{count}d{sides}!"),
+                        position,
+                        reason: "a single sided dice would explode forever"
+                    });
+                }
+
+                explode = true;
+            } else if keep.is_none() {
+                keep = self.keep()?;
+
+                if keep.is_none() {
+                    break;
+                }
+            } else {
+                break;
+            }
+        }
+
+        Ok(Term::Dice { count, sides, explode, keep })
+    }
+}
+
+impl FromStr for DiceExpression {
+    type Err = DiceError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(input);
+        let mut terms = Vec::new();
+        let mut dice = 0;
+
+        parser.skip_whitespace();
+
+        let mut negative = !parser.eat('+') && parser.eat('-');
+
+        loop {
+            parser.skip_whitespace();
+
+            let start = parser.position();
+            let term = parser.term()?;
+            let end = parser.position();
+
+            if let Term::Dice { count, .. } = term {
+                dice += count;
+
+                if dice > MAX_DICE {
+                    return Err(DiceError::LimitExceeded {
+                        token: input[start..end].to_string(),
+                        position: start,
+                        limit: MAX_DICE
+                    });
+                }
+            }
+
+            terms.push(SignedTerm {
+                negative,
+                text: input[start..end].to_string(),
+                term
+            });
+
+            if terms.len() > MAX_TERMS {
+                return Err(DiceError::TooManyTerms { limit: MAX_TERMS });
+            }
+
+            parser.skip_whitespace();
+
+            if parser.chars.peek().is_none() {
+                break;
+            }
+
+            negative = if parser.eat('+') {
+                false
+            } else if parser.eat('-') {
+                true
+            } else {
+                return Err(parser.unexpected("`+` or `-`"));
+            };
+        }
+
+        Ok(Self { terms })
+    }
+}
+
+impl Keep {
+    fn kept(self, count: usize) -> (usize, usize) {
+        let clamp = |n: u32| (n as usize).min(count);
+
+        match self {
+            Self::Highest(n) => (count - clamp(n), count),
+            Self::Lowest(n) => (0, clamp(n)),
+            Self::DropHighest(n) => (0, count - clamp(n)),
+            Self::DropLowest(n) => (clamp(n), count)
+        }
+    }
+}
+
+impl DiceExpression {
+    // exploding dice can roll more than this.
+    pub fn dice(&self) -> u32 {
+        self.terms
+            .iter()
+            .map(|SignedTerm { term, .. }| match term {
+                Term::Dice { count, .. } => *count,
+                Term::Constant(_) => 0
+            })
+            .sum()
+    }
+
+    pub fn roll(&self, frame: &Bytes) -> DiceRoll {
+        let mut terms = Vec::new();
+        let mut total = 0;
+
+        for SignedTerm { negative, text, term } in &self.terms {
+            let (dice, subtotal) = match term {
+                Term::Constant(value) => (Vec::new(), *value as i64),
+                Term::Dice { count, sides, explode, keep } => {
+                    let mut dice = Vec::new();
+                    let mut explosions = 0;
+
+                    for _ in 0..*count {
+                        let mut exploded = false;
+
+                        loop {
+                            let value = get_ranged(frame, *sides) + 1;
+
+                            dice.push(DieRoll { value, kept: true, exploded });
+
+                            if !explode || value != *sides || explosions >= MAX_EXPLOSIONS {
+                                break;
+                            }
+
+                            explosions += 1;
+                            exploded = true;
+                        }
+                    }
+
+                    if let Some(keep) = keep {
+                        let mut order = (0..dice.len()).collect::<Vec<_>>();
+
+                        order.sort_by_key(|index| dice[*index].value);
+
+                        let (from, to) = keep.kept(order.len());
+
+                        for (rank, index) in order.into_iter().enumerate() {
+                            dice[index].kept = (from..to).contains(&rank);
+                        }
+                    }
+
+                    let subtotal = dice
+                        .iter()
+                        .filter(|die| die.kept)
+                        .map(|die| die.value as i64)
+                        .sum();
+
+                    (dice, subtotal)
+                }
+            };
+
+            let sign = if *negative { -1 } else { 1 };
+
+            total += sign as i64 * subtotal;
+
+            terms.push(TermRoll {
+                term: text.clone(),
+                sign,
+                dice,
+                subtotal
+            });
+        }
+
+        DiceRoll {
+            expression: self
+                .terms
+                .iter()
+                .enumerate()
+                .map(|(index, term)| match (index, term.negative) {
+                    (0, false) => term.text.clone(),
+                    (0, true) => format!("-{}", term.text),
+                    (_, false) => format!(" + {}", term.text),
+                    (_, true) => format!(" - {}", term.text)
+                })
+                .collect(),
+            terms,
+            total
+        }
+    }
+}
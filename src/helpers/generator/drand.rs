@@ -0,0 +1,187 @@
+use std::{sync::OnceLock, time::Duration};
+use log::{info, warn};
+use serde::Serialize;
+use thiserror::Error;
+use time::OffsetDateTime;
+use tokio::time::sleep;
+use utoipa::ToSchema;
+use crate::{helpers::{authentication::signing::{sign_raw, verifying_key}, misc::{config::config, digest::{sha256, sha256_hex}}}, models::round::{Round, RoundError}};
+use super::{frame::{frame_bytes_from_source, CaptureError}, random::get_unsigned};
+
+// rounds are signed with ed25519 rather than drand's bls schemes, standard
+// drand and tlock clients can fetch them but not verify them.
+const SCHEME_ID: &str = "rlarndg-ed25519-chained";
+const SIGNATURE_SCHEME: &str = "ed25519";
+const GAP_CHAINING: &str = "last_emitted";
+
+#[derive(Debug, Error)]
+pub enum DrandError {
+    #[error("{0:#}")]
+    Capture(#[from] CaptureError<'static>),
+
+    #[error("{0:#}")]
+    Round(#[from] RoundError),
+
+    #[error("The signing key isn't loaded.")]
+    MissingKey
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ChainInfo {
+    public_key: String,
+    period: u64,
+    genesis_time: i64,
+    hash: String,
+    #[serde(rename = "groupHash")]
+    group_hash: String,
+    #[serde(rename = "schemeID")]
+    scheme_id: &'static str,
+    metadata: ChainMetadata
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ChainMetadata {
+    #[serde(rename = "beaconID")]
+    beacon_id: &'static str,
+    signature_scheme: &'static str,
+    gap_chaining: &'static str
+}
+
+pub struct DrandChain {
+    genesis_time: i64,
+    period: u64,
+    public_key: [u8; 32]
+}
+
+static CHAIN: OnceLock<Option<DrandChain>> = OnceLock::new();
+
+// the chain is only served when its genesis is configured, rounds are
+// signed so it also requires a signing key.
+pub fn drand_chain() -> Option<&'static DrandChain> {
+    CHAIN
+        .get_or_init(|| {
+            let genesis_time = config().drand.genesis?;
+            let period = config().drand.period;
+
+            let Some(public_key) = verifying_key() else {
+                warn!("The drand chain requires a signing key, set signing.key or pass --signing-key to enable it.");
+                return None;
+            };
+
+            Some(DrandChain {
+                genesis_time,
+                period,
+                public_key: public_key.to_bytes()
+            })
+        })
+        .as_ref()
+}
+
+impl DrandChain {
+    // used as the previous signature of the first round.
+    fn genesis_seed(&self) -> [u8; 32] {
+        sha256(&[&self.public_key[..], &self.genesis_time.to_be_bytes()].concat())
+    }
+
+    fn hash(&self) -> String {
+        sha256_hex(&[
+            &(self.period as u32).to_be_bytes()[..],
+            &self.genesis_time.to_be_bytes(),
+            &self.public_key,
+            &self.genesis_seed()
+        ].concat())
+    }
+
+    pub fn info(&self) -> ChainInfo {
+        ChainInfo {
+            public_key: hex::encode(self.public_key),
+            period: self.period,
+            genesis_time: self.genesis_time,
+            hash: self.hash(),
+            group_hash: hex::encode(self.genesis_seed()),
+            scheme_id: SCHEME_ID,
+            metadata: ChainMetadata {
+                beacon_id: "default",
+                signature_scheme: SIGNATURE_SCHEME,
+                gap_chaining: GAP_CHAINING
+            }
+        }
+    }
+
+    // rounds start at 1 on the genesis time, 0 means the chain didn't start yet.
+    pub fn round_at(&self, time: i64) -> i64 {
+        if time < self.genesis_time {
+            return 0;
+        }
+
+        (time - self.genesis_time) / self.period as i64 + 1
+    }
+
+    pub fn round_time(&self, round: i64) -> i64 {
+        self.genesis_time + (round - 1) * self.period as i64
+    }
+
+    // the signed message is the sha256 of the previous signature, the
+    // round as a big endian u64 and the local random value.
+    pub async fn emit_round(&self, round: i64) -> Result<Round, DrandError> {
+        let frame = frame_bytes_from_source().await?;
+
+        let local_random_value = (0..8)
+            .flat_map(|_| get_unsigned(&frame).to_be_bytes())
+            .collect::<Vec<_>>();
+
+        let previous_signature = match Round::latest().await? {
+            Some(previous) => hex::decode(previous.signature).map_err(RoundError::from)?,
+            None => self.genesis_seed().to_vec()
+        };
+
+        let message = sha256(&[
+            &previous_signature[..],
+            &(round as u64).to_be_bytes(),
+            &local_random_value
+        ].concat());
+
+        let round = Round {
+            round,
+            signature: hex::encode(sign_raw(&message).ok_or(DrandError::MissingKey)?),
+            previous_signature: hex::encode(previous_signature),
+            local_random_value: hex::encode(local_random_value),
+            segment_digests: vec![sha256_hex(&frame)],
+            created_at: OffsetDateTime::now_utc()
+        };
+
+        round.save().await?;
+
+        Ok(round)
+    }
+}
+
+// rounds missed while the server was down are never emitted, so the next
+// round's previous signature is the one of the last emitted round rather
+// than of round - 1.
+pub async fn run_drand(chain: &'static DrandChain) {
+    info!("Emitting drand rounds every {} seconds.", chain.period);
+
+    loop {
+        let now = OffsetDateTime::now_utc();
+        let current = chain.round_at(now.unix_timestamp());
+
+        if current > 0 {
+            match Round::latest().await {
+                Ok(latest) if latest.as_ref().map_or(true, |latest| latest.round < current) => {
+                    match chain.emit_round(current).await {
+                        Ok(round) => info!("Emitted drand round {}.", round.round),
+                        Err(error) => warn!("Couldn't emit a drand round, an error occurred: {error:#}")
+                    }
+                },
+                Ok(_) => {},
+                Err(error) => warn!("Couldn't fetch the latest drand round, an error occurred: {error:#}")
+            }
+        }
+
+        let next = chain.round_time(current + 1) as i128 * 1_000_000_000;
+        let wait = (next - OffsetDateTime::now_utc().unix_timestamp_nanos()).max(0);
+
+        sleep(Duration::from_nanos(wait as u64)).await;
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::{HashMap, HashSet}, env::args, fs::read_to_string, io::Error as IoError, str::FromStr, sync::{LazyLock, OnceLock}};
+use std::{collections::{HashMap, HashSet}, fs::read_to_string, io::Error as IoError, str::FromStr, sync::{LazyLock, OnceLock}};
 use actix_web::web::Bytes;
 use log::{info, warn};
 use reqwest::{header::{HeaderMap, HeaderName, HeaderValue, InvalidHeaderName, InvalidHeaderValue}, Client, Error as RequestError};
@@ -6,6 +6,8 @@ use serde::Deserialize;
 use serde_json::{from_str, Error as JsonError};
 use thiserror::Error;
 use tokio::sync::Mutex;
+use super::archive::archive_segment;
+use crate::helpers::{http::context::with_current, misc::{config::config, digest::sha256_hex}};
 
 #[macro_export]
 macro_rules! frame_bytes {
@@ -13,8 +15,9 @@ macro_rules! frame_bytes {
         match $crate::helpers::generator::frame::frame_bytes_from_source().await {
             Ok(bytes) => bytes,
             Err(err) => {
-                return actix_web::HttpResponse::InternalServerError()
-                    .body(format!("{err:#}"));
+                return actix_web::HttpResponse::from(
+                    $crate::helpers::http::errors::ApiError::entropy_unavailable(err)
+                );
             }
         }
     };
@@ -28,6 +31,9 @@ pub enum CaptureError<'s> {
     #[error("An empty playlist or invalid response was received from the server.")]
     InvalidResponse,
 
+    #[error("There are no sources loaded to capture frames from.")]
+    NoSources,
+
     #[error("{0:#}")]
     SourceError(&'s SourceError),
 
@@ -64,26 +70,20 @@ static STATE: LazyLock<Mutex<SourceState>> = LazyLock::new(|| Mutex::new(SourceS
 
 pub async fn frame_bytes_from_source<'r>() -> Result<Bytes, CaptureError<'r>> {
     let sources = SOURCES.get_or_init(|| {
-        let args = args()
-            .zip(args().skip(1))
-            .filter(|(arg, _)| arg == "--source")
-            .map(|(_, val)| val)
-            .collect::<Vec<String>>();
-
         let mut sources = Vec::new();
 
-        for source in args {
-            let parsed_sources = read_to_string(&source)
+        for path in &config().sources {
+            let parsed_sources = read_to_string(path)
                 .and_then(|source| Ok(from_str::<Vec<Source>>(&source)?));
 
             match parsed_sources {
                 Ok(parsed) => {
-                    info!("Loaded {source} successfully.");
+                    info!("Loaded {} successfully.", path.display());
 
                     sources.extend(parsed);
                 },
                 Err(error) => {
-                    warn!("Couldn't load source at {source}, an error occurred: {error:#}");
+                    warn!("Couldn't load source at {}, an error occurred: {error:#}", path.display());
                 }
             }
         }
@@ -104,13 +104,24 @@ pub async fn frame_bytes_from_source<'r>() -> Result<Bytes, CaptureError<'r>> {
         state.source_index += 1;
     }
 
-    if sources.len() == 0 || sources.len() >= state.source_index {
+    if sources.is_empty() || sources.len() >= state.source_index {
         state.source_index = 0;
     }
 
-    let source = &sources[state.source_index];
+    let source = sources
+        .get(state.source_index)
+        .ok_or(CaptureError::NoSources)?;
+
+    let bytes = frame_bytes(&source.source, &source.headers).await?;
+
+    drop(state);
+
+    let digest = sha256_hex(&bytes);
+
+    archive_segment(&digest, &bytes);
+    with_current(|context| context.segment_digests.push(digest));
 
-    frame_bytes(&source.source, &source.headers).await
+    Ok(bytes)
 }
 
 pub async fn frame_bytes<'p, 'r>(source: &'p String, source_headers: &'p Option<HashMap<String, String>>)
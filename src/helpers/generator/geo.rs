@@ -0,0 +1,163 @@
+use std::f64::consts::PI;
+use actix_web::web::Bytes;
+use serde::Serialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+use utoipa::ToSchema;
+use super::random::get_float;
+
+const EARTH_RADIUS_KM: f64 = 6371.0088;
+
+#[derive(Debug, Error)]
+pub enum GeoError {
+    #[error("The latitude {0} is out of range, expected a value between -90 and 90.")]
+    Latitude(f64),
+
+    #[error("The longitude {0} is out of range, expected a value between -180 and 180.")]
+    Longitude(f64),
+
+    #[error("The minimum latitude can't be greater than the maximum latitude.")]
+    InvertedBox,
+
+    #[error("The radius {0} is invalid, expected a positive amount of kilometers.")]
+    Radius(f64),
+
+    #[error("A bounding box and a center can't be used at the same time.")]
+    ConflictingArea,
+
+    #[error("Incomplete area, a bounding box needs min_lat, max_lat, min_lon and max_lon while a circle needs lat, lon and radius.")]
+    IncompleteArea
+}
+
+#[derive(Serialize, ToSchema, Clone, Copy)]
+pub struct Coordinates {
+    latitude: f64,
+    longitude: f64
+}
+
+pub enum GeoArea {
+    Globe,
+    BoundingBox {
+        min_latitude: f64,
+        max_latitude: f64,
+        min_longitude: f64,
+        max_longitude: f64
+    },
+    Circle {
+        center: Coordinates,
+        radius: f64
+    }
+}
+
+fn check_latitude(latitude: f64) -> Result<f64, GeoError> {
+    match (-90.0..=90.0).contains(&latitude) {
+        true => Ok(latitude),
+        false => Err(GeoError::Latitude(latitude))
+    }
+}
+
+fn check_longitude(longitude: f64) -> Result<f64, GeoError> {
+    match (-180.0..=180.0).contains(&longitude) {
+        true => Ok(longitude),
+        false => Err(GeoError::Longitude(longitude))
+    }
+}
+
+fn normalize_longitude(longitude: f64) -> f64 {
+    (longitude + 180.0).rem_euclid(360.0) - 180.0
+}
+
+impl Coordinates {
+    pub fn new(latitude: f64, longitude: f64) -> Result<Self, GeoError> {
+        Ok(Self {
+            latitude: check_latitude(latitude)?,
+            longitude: check_longitude(longitude)?
+        })
+    }
+
+    pub fn as_geojson(&self) -> Value {
+        json!({
+            "type": "Feature",
+            "geometry": {
+                "type": "Point",
+                "coordinates": [self.longitude, self.latitude]
+            },
+            "properties": {}
+        })
+    }
+}
+
+impl GeoArea {
+    pub fn bounding_box(min_latitude: f64, max_latitude: f64, min_longitude: f64, max_longitude: f64)
+        -> Result<Self, GeoError> {
+
+        if check_latitude(min_latitude)? > check_latitude(max_latitude)? {
+            return Err(GeoError::InvertedBox);
+        }
+
+        Ok(Self::BoundingBox {
+            min_latitude,
+            max_latitude,
+            min_longitude: check_longitude(min_longitude)?,
+            max_longitude: check_longitude(max_longitude)?
+        })
+    }
+
+    pub fn circle(center: Coordinates, radius: f64) -> Result<Self, GeoError> {
+        if !radius.is_finite() || radius <= 0.0 {
+            return Err(GeoError::Radius(radius));
+        }
+
+        Ok(Self::Circle { center, radius })
+    }
+
+    pub fn random_point(&self, frame: &Bytes) -> Coordinates {
+        match self {
+            Self::Globe => Self::random_in_box(frame, -90.0, 90.0, -180.0, 180.0),
+            Self::BoundingBox { min_latitude, max_latitude, min_longitude, max_longitude } => {
+                Self::random_in_box(frame, *min_latitude, *max_latitude, *min_longitude, *max_longitude)
+            },
+            Self::Circle { center, radius } => Self::random_in_circle(frame, center, *radius)
+        }
+    }
+
+    // the sine of the latitude is uniform over the surface, sampling it
+    // instead of the latitude avoids clustering points near the poles.
+    fn random_in_box(frame: &Bytes, min_latitude: f64, max_latitude: f64, min_longitude: f64, max_longitude: f64)
+        -> Coordinates {
+
+        let (low, high) = (min_latitude.to_radians().sin(), max_latitude.to_radians().sin());
+        let latitude = (low + get_float(frame) * (high - low)).asin().to_degrees();
+
+        // a box whose minimum longitude is greater than its maximum crosses the antimeridian.
+        let span = (max_longitude - min_longitude).rem_euclid(360.0);
+        let span = if span == 0.0 && max_longitude != min_longitude { 360.0 } else { span };
+        let longitude = normalize_longitude(min_longitude + get_float(frame) * span);
+
+        Coordinates { latitude, longitude }
+    }
+
+    // samples a spherical cap, the cosine of the angular distance to the
+    // center is uniform over the cap area.
+    fn random_in_circle(frame: &Bytes, center: &Coordinates, radius: f64) -> Coordinates {
+        let angular_radius = (radius / EARTH_RADIUS_KM).min(PI);
+        let distance = (1.0 - get_float(frame) * (1.0 - angular_radius.cos())).acos();
+        let bearing = 2.0 * PI * get_float(frame);
+
+        let latitude = center.latitude.to_radians();
+        let longitude = center.longitude.to_radians();
+
+        let target_latitude = (latitude.sin() * distance.cos()
+            + latitude.cos() * distance.sin() * bearing.cos())
+            .clamp(-1.0, 1.0)
+            .asin();
+
+        let target_longitude = longitude + (bearing.sin() * distance.sin() * latitude.cos())
+            .atan2(distance.cos() - latitude.sin() * target_latitude.sin());
+
+        Coordinates {
+            latitude: target_latitude.to_degrees(),
+            longitude: normalize_longitude(target_longitude.to_degrees())
+        }
+    }
+}
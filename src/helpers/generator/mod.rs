@@ -1,3 +1,13 @@
 
+pub mod archive;
+pub mod beacon;
+pub mod bigint;
+pub mod choice;
+pub mod datetime;
+pub mod dice;
+pub mod drand;
 pub mod frame;
+pub mod geo;
 pub mod random;
+pub mod seed;
+pub mod stream;
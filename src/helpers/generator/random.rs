@@ -11,8 +11,78 @@ pub fn get_unsigned(frame: &Bytes) -> u32 {
         | (frame[start + 3] as u32)
 }
 
+// the negation wraps so i32::MIN stays as is instead of overflowing,
+// every value keeps the same odds since it's still a bijection.
+pub fn get_signed(frame: &Bytes) -> i32 {
+    let number = get_unsigned(frame) as i32;
+
+    match get_bool(frame) {
+        true => number.wrapping_neg(),
+        false => number
+    }
+}
+
 pub fn get_bool(frame: &Bytes) -> bool {
     let start = thread_rng().gen_range(0..frame.len());
 
     frame[start] & 1 != 0
 }
+
+pub fn get_ranged(frame: &Bytes, bound: u32) -> u32 {
+    // values above the last multiple of `bound` are rejected so every
+    // remainder has the same amount of values mapping to it.
+    let zone = u32::MAX - (u32::MAX - bound + 1) % bound;
+
+    loop {
+        let value = get_unsigned(frame);
+
+        if value <= zone {
+            return value % bound;
+        }
+    }
+}
+
+pub fn get_ranged_wide(frame: &Bytes, bound: u64) -> u64 {
+    let zone = u64::MAX - (u64::MAX - bound + 1) % bound;
+
+    loop {
+        let value = ((get_unsigned(frame) as u64) << 32) | get_unsigned(frame) as u64;
+
+        if value <= zone {
+            return value % bound;
+        }
+    }
+}
+
+pub fn get_float(frame: &Bytes) -> f64 {
+    let bits = ((get_unsigned(frame) as u64) << 32) | get_unsigned(frame) as u64;
+
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+pub fn get_uuid(frame: &Bytes) -> String {
+    let mut bytes = (0..4)
+        .flat_map(|_| get_unsigned(frame).to_be_bytes())
+        .collect::<Vec<_>>();
+
+    // version 4 and the RFC 4122 variant.
+    bytes[6] = (bytes[6] & 0x0F) | 0x40;
+    bytes[8] = (bytes[8] & 0x3F) | 0x80;
+
+    let hex = hex::encode(bytes);
+
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+pub fn shuffle<T>(frame: &Bytes, values: &mut [T]) {
+    for index in (1..values.len()).rev() {
+        values.swap(index, get_ranged(frame, index as u32 + 1) as usize);
+    }
+}
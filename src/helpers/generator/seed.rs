@@ -0,0 +1,96 @@
+use std::str::FromStr;
+use actix_web::web::Bytes;
+use rand::{RngCore, SeedableRng};
+use rand_chacha::ChaCha20Rng;
+use rand_pcg::Pcg64;
+use rand_xoshiro::Xoshiro256PlusPlus;
+use serde::Serialize;
+use thiserror::Error;
+use utoipa::ToSchema;
+use super::random::get_unsigned;
+
+const MAX_OUTPUTS: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum SeedError {
+    #[error("Invalid algorithm `{0}`, expected one of chacha20, pcg64 or xoshiro256.")]
+    InvalidAlgorithm(String),
+
+    #[error("At most {MAX_OUTPUTS} outputs can be requested.")]
+    TooManyOutputs
+}
+
+#[derive(Clone, Copy)]
+pub enum SeedAlgorithm {
+    ChaCha20,
+    Pcg64,
+    Xoshiro256
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Seed {
+    algorithm: &'static str,
+    seed: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    outputs: Option<Vec<String>>
+}
+
+pub fn check_outputs(outputs: Option<usize>) -> Result<(), SeedError> {
+    match outputs.is_some_and(|outputs| outputs > MAX_OUTPUTS) {
+        true => Err(SeedError::TooManyOutputs),
+        false => Ok(())
+    }
+}
+
+impl FromStr for SeedAlgorithm {
+    type Err = SeedError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "chacha20" => Ok(Self::ChaCha20),
+            "pcg64" => Ok(Self::Pcg64),
+            "xoshiro256" => Ok(Self::Xoshiro256),
+            _ => Err(SeedError::InvalidAlgorithm(value.to_string()))
+        }
+    }
+}
+
+impl SeedAlgorithm {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::ChaCha20 => "chacha20",
+            Self::Pcg64 => "pcg64",
+            Self::Xoshiro256 => "xoshiro256++"
+        }
+    }
+
+    // the generators are seeded the same way SeedableRng::from_seed does
+    // in the rand_chacha, rand_pcg and rand_xoshiro crates.
+    fn outputs(&self, seed: [u8; 32], count: usize) -> Vec<String> {
+        let mut rng: Box<dyn RngCore> = match self {
+            Self::ChaCha20 => Box::new(ChaCha20Rng::from_seed(seed)),
+            Self::Pcg64 => Box::new(Pcg64::from_seed(seed)),
+            Self::Xoshiro256 => Box::new(Xoshiro256PlusPlus::from_seed(seed))
+        };
+
+        (0..count)
+            .map(|_| format!("{:016x}", rng.next_u64()))
+            .collect()
+    }
+
+    pub fn seed(self, frame: &Bytes, outputs: Option<usize>) -> Result<Seed, SeedError> {
+        check_outputs(outputs)?;
+
+        let mut seed = [0u8; 32];
+
+        for chunk in seed.chunks_mut(4) {
+            chunk.copy_from_slice(&get_unsigned(frame).to_be_bytes());
+        }
+
+        Ok(Seed {
+            algorithm: self.name(),
+            seed: hex::encode(seed),
+            outputs: outputs.map(|count| self.outputs(seed, count))
+        })
+    }
+}
@@ -0,0 +1,220 @@
+use std::{collections::HashMap, str::FromStr, sync::{LazyLock, Mutex}, time::Duration};
+use actix_web::web::Bytes;
+use log::warn;
+use serde::Serialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+use time::OffsetDateTime;
+use tokio::time::{interval, Instant, Interval, MissedTickBehavior};
+use utoipa::ToSchema;
+use crate::models::key::{ApiKey, QuotaError};
+use super::{frame::frame_bytes_from_source, random::{get_bool, get_float, get_signed, get_unsigned}};
+
+// a new segment is captured once the current one is this old.
+pub const FRAME_LIFETIME: Duration = Duration::from_secs(5);
+// one value every 100 seconds, lower rates would overflow the tick interval.
+const MIN_RATE: f64 = 0.01;
+
+#[derive(Debug, Error)]
+pub enum StreamError {
+    #[error("Invalid type `{0}`, expected one of unsigned, signed, boolean or float.")]
+    InvalidKind(String),
+
+    #[error("Invalid rate, expected from {MIN_RATE} up to {0} values per second for this key.")]
+    InvalidRate(f64),
+
+    #[error("This key already streams {0} of its {1} values per second, close a stream or lower the rate.")]
+    Throughput(f64, f64),
+
+    #[error("{0}")]
+    Quota(#[from] QuotaError)
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct StreamValue {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    value: Value,
+    timestamp: i64
+}
+
+// why a stream ended, sent to the client before it's closed.
+#[derive(Serialize, ToSchema)]
+pub struct StreamEnd {
+    code: &'static str,
+    message: String
+}
+
+#[derive(Clone, Copy)]
+pub enum StreamKind {
+    Unsigned,
+    Signed,
+    Boolean,
+    Float
+}
+
+// the rate each key is streaming at, summed over its open streams.
+static THROUGHPUT: LazyLock<Mutex<HashMap<i32, f64>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+pub struct Subscription {
+    key: ApiKey,
+    kind: StreamKind,
+    rate: f64,
+    ticks: Interval,
+    frame: Option<(Bytes, Instant)>,
+    sequence: u64
+}
+
+impl FromStr for StreamKind {
+    type Err = StreamError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "unsigned" => Ok(Self::Unsigned),
+            "signed" => Ok(Self::Signed),
+            "boolean" => Ok(Self::Boolean),
+            "float" => Ok(Self::Float),
+            _ => Err(StreamError::InvalidKind(value.to_string()))
+        }
+    }
+}
+
+impl StreamKind {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Unsigned => "unsigned",
+            Self::Signed => "signed",
+            Self::Boolean => "boolean",
+            Self::Float => "float"
+        }
+    }
+
+    fn bits(&self) -> u64 {
+        match self {
+            Self::Unsigned | Self::Signed => 32,
+            Self::Boolean => 1,
+            Self::Float => 53
+        }
+    }
+
+    fn generate(&self, frame: &Bytes) -> Value {
+        match self {
+            Self::Unsigned => json!(get_unsigned(frame)),
+            Self::Signed => json!(get_signed(frame)),
+            Self::Boolean => json!(get_bool(frame)),
+            Self::Float => json!(get_float(frame))
+        }
+    }
+}
+
+impl StreamEnd {
+    fn new(code: &'static str, message: impl ToString) -> Self {
+        Self {
+            code,
+            message: message.to_string()
+        }
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+}
+
+impl Subscription {
+    // the rate is reserved from the key's throughput until the subscription
+    // is dropped, opening it counts as a request.
+    pub fn open(key: ApiKey, kind: StreamKind, rate: f64) -> Result<Self, StreamError> {
+        let max_rate = key.tier().max_stream_rate();
+
+        if !(MIN_RATE..=max_rate).contains(&rate) {
+            return Err(StreamError::InvalidRate(max_rate));
+        }
+
+        // built before taking the lock so nothing in the critical section can panic.
+        let mut ticks = interval(Duration::from_secs_f64(1.0 / rate));
+
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let mut throughput = THROUGHPUT
+            .lock()
+            .unwrap();
+
+        let used = throughput
+            .get(&key.id)
+            .copied()
+            .unwrap_or(0.0);
+
+        if used + rate > max_rate {
+            return Err(StreamError::Throughput(used, max_rate));
+        }
+
+        key.consume(0)?;
+
+        *throughput
+            .entry(key.id)
+            .or_insert(0.0) += rate;
+
+        Ok(Self {
+            key,
+            kind,
+            rate,
+            ticks,
+            frame: None,
+            sequence: 0
+        })
+    }
+
+    async fn frame(&mut self) -> Result<Bytes, StreamEnd> {
+        if let Some((frame, captured)) = &self.frame {
+            if captured.elapsed() < FRAME_LIFETIME {
+                return Ok(frame.clone());
+            }
+        }
+
+        let frame = frame_bytes_from_source()
+            .await
+            .map_err(|error| {
+                warn!("Couldn't capture a frame for a stream, an error occurred: {error:#}");
+                StreamEnd::new("entropy_unavailable", "The entropy source is unavailable, try again later.")
+            })?;
+
+        self.frame = Some((frame.clone(), Instant::now()));
+
+        Ok(frame)
+    }
+
+    // waits for the next tick and returns the event id with its payload.
+    pub async fn next(&mut self) -> Result<(u64, StreamValue), StreamEnd> {
+        self.ticks.tick().await;
+
+        let frame = self.frame().await?;
+
+        self.key
+            .consume_bits(self.kind.bits())
+            .map_err(|error| StreamEnd::new("quota_exhausted", error))?;
+
+        self.sequence += 1;
+
+        Ok((self.sequence, StreamValue {
+            kind: self.kind.name(),
+            value: self.kind.generate(&frame),
+            timestamp: OffsetDateTime::now_utc().unix_timestamp()
+        }))
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let mut throughput = THROUGHPUT
+            .lock()
+            .unwrap();
+
+        if let Some(used) = throughput.get_mut(&self.key.id) {
+            *used -= self.rate;
+
+            if *used <= f64::EPSILON {
+                throughput.remove(&self.key.id);
+            }
+        }
+    }
+}
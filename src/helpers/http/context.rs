@@ -0,0 +1,99 @@
+use std::{cell::RefCell, future::Future, rc::Rc};
+use actix_web::dev::ServiceRequest;
+use rand::{thread_rng, RngCore};
+use serde::Serialize;
+use serde_json::{to_value, Value};
+use tokio::{task::futures::TaskLocalFuture, task_local};
+use super::negotiation::ResponseFormat;
+
+task_local! {
+    static CONTEXT: Rc<RefCell<RequestContext>>;
+}
+
+pub struct PendingReceipt {
+    pub id: String,
+    pub key_id: Option<i32>,
+    pub value: Value,
+    pub timestamp: i64
+}
+
+pub struct RequestContext {
+    pub request_id: String,
+    pub endpoint: String,
+    pub parameters: String,
+    pub body: Option<Value>,
+    pub format: ResponseFormat,
+    pub segment_digests: Vec<String>,
+    pub receipt: Option<PendingReceipt>
+}
+
+// a request id sent by a proxy is kept so logs can be correlated.
+fn request_id(req: &ServiceRequest) -> String {
+    let forwarded = req
+        .headers()
+        .get("X-Request-Id")
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| (1..=64).contains(&value.len()))
+        .filter(|value| value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+
+    if let Some(id) = forwarded {
+        return id.to_string();
+    }
+
+    let mut id = [0u8; 16];
+
+    thread_rng().fill_bytes(&mut id);
+
+    hex::encode(id)
+}
+
+impl RequestContext {
+    // the format is negotiated first so an output format override isn't
+    // kept in the parameters.
+    pub fn new(req: &mut ServiceRequest) -> Rc<RefCell<Self>> {
+        let format = ResponseFormat::negotiate(req);
+
+        Rc::new(RefCell::new(Self {
+            request_id: request_id(req),
+            endpoint: req.path().to_string(),
+            parameters: req.query_string().to_string(),
+            body: None,
+            format,
+            segment_digests: Vec::new(),
+            receipt: None
+        }))
+    }
+}
+
+// runs both the service call and the future it returns with the context
+// available, so extractors, handlers and responses can reach it.
+pub fn with_context<F, Fut>(context: Rc<RefCell<RequestContext>>, call: F)
+    -> TaskLocalFuture<Rc<RefCell<RequestContext>>, Fut>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future
+{
+    let future = CONTEXT.sync_scope(context.clone(), call);
+
+    CONTEXT.scope(context, future)
+}
+
+pub fn with_current<R>(action: impl FnOnce(&mut RequestContext) -> R) -> Option<R> {
+    CONTEXT
+        .try_with(|context| action(&mut context.borrow_mut()))
+        .ok()
+}
+
+// handlers taking a body record it so receipts keep every input of the request.
+pub fn record_body(body: &impl Serialize) {
+    with_current(|context| context.body = to_value(body).ok());
+}
+
+pub fn current_request_id() -> Option<String> {
+    with_current(|context| context.request_id.clone())
+}
+
+pub fn current_format() -> ResponseFormat {
+    with_current(|context| context.format)
+        .unwrap_or_default()
+}
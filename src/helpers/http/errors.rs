@@ -0,0 +1,287 @@
+use std::fmt::Display;
+use actix_web::{body::BoxBody, http::StatusCode, web::{JsonConfig, PathConfig, QueryConfig, ServiceConfig}, HttpResponse, ResponseError};
+use log::error;
+use serde::Serialize;
+use serde_json::{to_value, Value};
+use thiserror::Error;
+use utoipa::ToSchema;
+use crate::{helpers::{generator::{bigint::BigIntError, choice::ChoiceError, datetime::DateTimeError, dice::DiceError, geo::GeoError, seed::SeedError, stream::StreamError}, misc::color::ColorError}, models::{deck::DeckError, draw::DrawError, key::QuotaError, user::UserError}};
+use super::context::current_request_id;
+
+#[derive(Debug, Error)]
+#[error("{message}")]
+pub struct ApiError {
+    status: StatusCode,
+    code: &'static str,
+    message: String,
+    details: Option<Value>
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ErrorBody<'e> {
+    #[schema(example = "invalid_format")]
+    code: &'static str,
+    message: &'e str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: &'e Option<Value>
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ErrorEnvelope<'e> {
+    error: ErrorBody<'e>
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &'static str, message: impl Display) -> Self {
+        Self {
+            status,
+            code,
+            message: message.to_string(),
+            details: None
+        }
+    }
+
+    pub fn bad_request(code: &'static str, message: impl Display) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, code, message)
+    }
+
+    pub fn unauthorized(code: &'static str, message: impl Display) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, code, message)
+    }
+
+    pub fn not_found(code: &'static str, message: impl Display) -> Self {
+        Self::new(StatusCode::NOT_FOUND, code, message)
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    // for errors reported inside a successful response, the request id is
+    // already in the response headers.
+    pub fn body(&self) -> ErrorBody<'_> {
+        ErrorBody {
+            code: self.code,
+            message: &self.message,
+            request_id: None,
+            details: &self.details
+        }
+    }
+
+    // the error is only logged, clients get a generic message and the
+    // request id to report it.
+    fn logged(status: StatusCode, code: &'static str, message: &str, error: impl Display) -> Self {
+        error!(
+            "Request {} failed with {code}: {error:#}",
+            current_request_id().unwrap_or_else(|| "-".into())
+        );
+
+        Self::new(status, code, message)
+    }
+
+    pub fn internal(error: impl Display) -> Self {
+        Self::logged(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "internal_error",
+            "An internal error occurred, try again later.",
+            error
+        )
+    }
+
+    pub fn entropy_unavailable(error: impl Display) -> Self {
+        Self::logged(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "entropy_unavailable",
+            "The entropy source is unavailable, try again later.",
+            error
+        )
+    }
+}
+
+impl ResponseError for ApiError {
+    fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    fn error_response(&self) -> HttpResponse<BoxBody> {
+        HttpResponse::build(self.status)
+            .json(ErrorEnvelope {
+                error: ErrorBody {
+                    code: self.code,
+                    message: &self.message,
+                    request_id: current_request_id(),
+                    details: &self.details
+                }
+            })
+    }
+}
+
+impl From<ApiError> for HttpResponse<BoxBody> {
+    fn from(error: ApiError) -> Self {
+        error.error_response()
+    }
+}
+
+// extractor errors use the same envelope as the handlers.
+pub fn configure_extractors(config: &mut ServiceConfig) {
+    config
+        .app_data(JsonConfig::default().error_handler(|error, _| {
+            ApiError::bad_request("invalid_body", error).into()
+        }))
+        .app_data(QueryConfig::default().error_handler(|error, _| {
+            ApiError::bad_request("invalid_query", error).into()
+        }))
+        .app_data(PathConfig::default().error_handler(|error, _| {
+            ApiError::bad_request("invalid_path", error).into()
+        }));
+}
+
+pub async fn route_not_found() -> HttpResponse {
+    ApiError::not_found("route_not_found", "The requested route doesn't exist.")
+        .into()
+}
+
+impl From<ColorError> for ApiError {
+    fn from(error: ColorError) -> Self {
+        let code = match error {
+            ColorError::InvalidColor(_) => "invalid_color",
+            ColorError::InvalidFormat(_) => "invalid_format",
+            ColorError::InvalidScheme(_) => "invalid_scheme",
+            ColorError::UnreachableContrast(_) => "unreachable_contrast"
+        };
+
+        Self::bad_request(code, error)
+    }
+}
+
+impl From<DiceError> for ApiError {
+    fn from(error: DiceError) -> Self {
+        let details = to_value(&error).ok();
+        let error = Self::bad_request("invalid_dice", error);
+
+        match details {
+            Some(details) => error.with_details(details),
+            None => error
+        }
+    }
+}
+
+impl From<GeoError> for ApiError {
+    fn from(error: GeoError) -> Self {
+        let code = match error {
+            GeoError::Latitude(_) => "invalid_latitude",
+            GeoError::Longitude(_) => "invalid_longitude",
+            GeoError::InvertedBox => "inverted_box",
+            GeoError::Radius(_) => "invalid_radius",
+            GeoError::ConflictingArea => "conflicting_area",
+            GeoError::IncompleteArea => "incomplete_area"
+        };
+
+        Self::bad_request(code, error)
+    }
+}
+
+impl From<DateTimeError> for ApiError {
+    fn from(error: DateTimeError) -> Self {
+        let code = match error {
+            DateTimeError::InvalidInstant(_) => "invalid_instant",
+            DateTimeError::InvalidDate(_) => "invalid_date",
+            DateTimeError::InvalidOffset(_) => "invalid_offset",
+            DateTimeError::InvertedRange => "inverted_range",
+            DateTimeError::UnsupportedOffset(_) => "unsupported_offset",
+            DateTimeError::InvalidFormat(_) => "invalid_format",
+            DateTimeError::Format(_) => "unformattable_value"
+        };
+
+        Self::bad_request(code, error)
+    }
+}
+
+impl From<BigIntError> for ApiError {
+    fn from(error: BigIntError) -> Self {
+        match error {
+            BigIntError::BitsOutOfRange { .. } => Self::bad_request("bits_out_of_range", error),
+            BigIntError::InvalidFormat(_) => Self::bad_request("invalid_format", error),
+            BigIntError::Timeout(_) | BigIntError::Cancelled => {
+                Self::new(StatusCode::SERVICE_UNAVAILABLE, "generation_timeout", error)
+            },
+            BigIntError::Join(_) => Self::internal(error)
+        }
+    }
+}
+
+impl From<SeedError> for ApiError {
+    fn from(error: SeedError) -> Self {
+        let code = match error {
+            SeedError::InvalidAlgorithm(_) => "invalid_algorithm",
+            SeedError::TooManyOutputs => "too_many_outputs"
+        };
+
+        Self::bad_request(code, error)
+    }
+}
+
+impl From<ChoiceError> for ApiError {
+    fn from(error: ChoiceError) -> Self {
+        let code = match error {
+            ChoiceError::ItemCount => "invalid_item_count",
+            ChoiceError::PickCount => "invalid_pick_count",
+            ChoiceError::TotalWeight => "invalid_total_weight",
+            ChoiceError::NotEnoughItems(..) => "not_enough_items"
+        };
+
+        Self::bad_request(code, error)
+    }
+}
+
+impl From<DeckError> for ApiError {
+    fn from(error: DeckError) -> Self {
+        match error {
+            DeckError::DeckCount => Self::bad_request("invalid_deck_count", error),
+            DeckError::CustomCards => Self::bad_request("invalid_custom_cards", error),
+            DeckError::NotEnoughCards(_) => Self::bad_request("not_enough_cards", error),
+            DeckError::Query(_) | DeckError::Connection(_) => Self::internal(error)
+        }
+    }
+}
+
+impl From<DrawError> for ApiError {
+    fn from(error: DrawError) -> Self {
+        match error {
+            DrawError::Participants => Self::bad_request("invalid_participants", error),
+            DrawError::Winners => Self::bad_request("invalid_winners", error),
+            DrawError::DrawTime => Self::bad_request("invalid_draw_time", error),
+            DrawError::Capture(error) => Self::entropy_unavailable(error),
+            DrawError::Query(_) | DrawError::Connection(_) | DrawError::Json(_) => Self::internal(error)
+        }
+    }
+}
+
+impl From<UserError> for ApiError {
+    fn from(error: UserError) -> Self {
+        match error {
+            UserError::EmailConflict(_) => Self::bad_request("email_conflict", error),
+            _ => Self::internal(error)
+        }
+    }
+}
+
+impl From<StreamError> for ApiError {
+    fn from(error: StreamError) -> Self {
+        match error {
+            StreamError::InvalidKind(_) => Self::bad_request("invalid_type", error),
+            StreamError::InvalidRate(_) => Self::bad_request("invalid_rate", error),
+            StreamError::Throughput(..) => Self::new(StatusCode::TOO_MANY_REQUESTS, "stream_limit", error),
+            StreamError::Quota(error) => error.into()
+        }
+    }
+}
+
+impl From<QuotaError> for ApiError {
+    fn from(error: QuotaError) -> Self {
+        Self::new(StatusCode::TOO_MANY_REQUESTS, "quota_exhausted", error)
+    }
+}
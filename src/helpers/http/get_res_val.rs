@@ -5,8 +5,9 @@ macro_rules! grv {
         match $e {
             Ok(v) => v,
             Err(e) => {
-                return actix_web::HttpResponse::InternalServerError()
-                    .body(format!("{e:#}"))
+                return actix_web::HttpResponse::from(
+                    $crate::helpers::http::errors::ApiError::internal(e)
+                );
             }
         }
     }};
@@ -14,12 +15,11 @@ macro_rules! grv {
 
 #[macro_export]
 macro_rules! gov {
-    ($e:expr, $err:literal) => {
+    ($e:expr, $err:expr) => {
         match $e {
             Some(v) => v,
             None => {
-                return actix_web::HttpResponse::InternalServerError()
-                    .body(format!("{:#}", $err))
+                return actix_web::HttpResponse::from($err);
             }
         }
     };
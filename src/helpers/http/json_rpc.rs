@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{from_slice, from_value, Value};
+use thiserror::Error;
+use utoipa::ToSchema;
+use crate::models::key::QuotaError;
+
+#[derive(Debug, Error)]
+pub enum RpcError {
+    #[error("Parse error")]
+    Parse,
+
+    #[error("Invalid Request")]
+    InvalidRequest,
+
+    #[error("Method not found")]
+    MethodNotFound,
+
+    #[error("Invalid params: {0}")]
+    InvalidParams(String),
+
+    #[error("Internal error")]
+    Internal,
+
+    #[error("Parameter '{0}' is malformed")]
+    Malformed(&'static str),
+
+    #[error("Parameter '{name}' is out of range. Allowable values are [{min}, {max}]")]
+    OutOfRange { name: &'static str, min: i64, max: i64 },
+
+    #[error("Parameter 'min' must be less than or equal to parameter 'max'")]
+    MinGreaterThanMax,
+
+    #[error("You requested {requested} values without replacement but there are only {possible} possible values")]
+    NotEnoughValues { requested: u64, possible: u64 },
+
+    #[error("The API key you specified does not exist")]
+    UnknownKey,
+
+    #[error("{0}")]
+    Quota(#[from] QuotaError)
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RpcRequest {
+    jsonrpc: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+    #[serde(default)]
+    pub id: Value
+}
+
+#[derive(Serialize, ToSchema)]
+struct RpcErrorBody {
+    code: i32,
+    message: String
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RpcResponse {
+    jsonrpc: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcErrorBody>,
+    id: Value
+}
+
+impl RpcError {
+    // the codes follow the JSON-RPC 2.0 spec and the ones documented by random.org.
+    pub fn code(&self) -> i32 {
+        match self {
+            Self::Parse => -32700,
+            Self::InvalidRequest => -32600,
+            Self::MethodNotFound => -32601,
+            Self::InvalidParams(_) => -32602,
+            Self::Internal => -32603,
+            Self::Malformed(_) => 200,
+            Self::OutOfRange { .. } => 202,
+            Self::MinGreaterThanMax => 300,
+            Self::NotEnoughValues { .. } => 301,
+            Self::UnknownKey => 400,
+            Self::Quota(QuotaError::Requests) => 402,
+            Self::Quota(QuotaError::Bits) => 403
+        }
+    }
+}
+
+impl RpcRequest {
+    pub fn parse(body: &[u8]) -> Result<Self, RpcError> {
+        let request = from_value::<Self>(
+            from_slice::<Value>(body)
+                .map_err(|_| RpcError::Parse)?
+        )
+            .map_err(|_| RpcError::InvalidRequest)?;
+
+        if request.jsonrpc != "2.0" {
+            return Err(RpcError::InvalidRequest);
+        }
+
+        Ok(request)
+    }
+}
+
+impl RpcResponse {
+    pub fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(RpcErrorBody {
+                code: error.code(),
+                message: error.to_string()
+            }))
+        };
+
+        Self {
+            jsonrpc: "2.0",
+            result,
+            error,
+            id
+        }
+    }
+}
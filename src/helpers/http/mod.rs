@@ -1,3 +1,10 @@
 
+pub mod context;
+pub mod errors;
+pub mod json_rpc;
 pub mod responses;
+pub mod versioning;
+pub mod negotiation;
 pub mod get_res_val;
+pub mod tls;
+pub mod query;
@@ -0,0 +1,206 @@
+use std::{io::Error as IoError, str::FromStr};
+use actix_web::{dev::ServiceRequest, http::{header::{Accept, Header}, uri::{PathAndQuery, Uri}}};
+use ciborium::{into_writer, ser::Error as CborError};
+use rmp_serde::{encode::Error as MessagePackError, to_vec_named};
+use serde::Serialize;
+use serde_json::{to_value, to_vec, Error as JsonError, Value};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum EncodeError {
+    #[error("Json: {0:#}")]
+    Json(#[from] JsonError),
+
+    #[error("MessagePack: {0:#}")]
+    MessagePack(#[from] MessagePackError),
+
+    #[error("Cbor: {0:#}")]
+    Cbor(#[from] CborError<IoError>)
+}
+
+const OUTPUT_PREFIX: &str = "output=";
+
+#[derive(Clone, Copy, Default)]
+pub enum ResponseFormat {
+    #[default]
+    Json,
+    Text,
+    Csv,
+    MessagePack,
+    Cbor
+}
+
+impl ResponseFormat {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(Self::Json),
+            "text" => Some(Self::Text),
+            "csv" => Some(Self::Csv),
+            "msgpack" => Some(Self::MessagePack),
+            "cbor" => Some(Self::Cbor),
+            _ => None
+        }
+    }
+
+    fn from_media_type(kind: &str, subtype: &str) -> Option<Self> {
+        match (kind, subtype) {
+            ("*", "*") | ("application", "*") | ("application", "json") => Some(Self::Json),
+            ("text", "*") | ("text", "plain") => Some(Self::Text),
+            ("text", "csv") => Some(Self::Csv),
+            ("application", "msgpack" | "x-msgpack" | "vnd.msgpack") => Some(Self::MessagePack),
+            ("application", "cbor") => Some(Self::Cbor),
+            _ => None
+        }
+    }
+
+    // the `output` parameter wins over the Accept header and is always removed
+    // from the query, `format` is left to the handlers that use it for the value.
+    pub fn negotiate(req: &mut ServiceRequest) -> Self {
+        let query = req.query_string().to_string();
+
+        let (overrides, rest) = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .partition::<Vec<_>, _>(|pair| pair.starts_with(OUTPUT_PREFIX));
+
+        if !overrides.is_empty() {
+            replace_query(req, &rest.join("&"));
+        }
+
+        if let Some(format) = overrides
+            .iter()
+            .rev()
+            .filter_map(|pair| pair.strip_prefix(OUTPUT_PREFIX))
+            .find_map(Self::from_name)
+        {
+            return format;
+        }
+
+        Accept::parse(req)
+            .ok()
+            .and_then(|accept| {
+                accept
+                    .ranked()
+                    .iter()
+                    .find_map(|mime| Self::from_media_type(mime.type_().as_str(), mime.subtype().as_str()))
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Text => "text/plain; charset=utf-8",
+            Self::Csv => "text/csv; charset=utf-8",
+            Self::MessagePack => "application/msgpack",
+            Self::Cbor => "application/cbor"
+        }
+    }
+
+    // json and the binary formats carry the whole response, text and csv
+    // only carry the value so scripts can use it as is.
+    pub fn encode(&self, response: &impl Serialize, value: &impl Serialize) -> Result<Vec<u8>, EncodeError> {
+        Ok(match self {
+            Self::Json => to_vec(response)?,
+            Self::Text => render_text(&to_value(value)?).into_bytes(),
+            Self::Csv => render_csv(&to_value(value)?).into_bytes(),
+            Self::MessagePack => to_vec_named(response)?,
+            Self::Cbor => {
+                let mut bytes = Vec::new();
+                into_writer(response, &mut bytes)?;
+                bytes
+            }
+        })
+    }
+}
+
+fn replace_query(req: &mut ServiceRequest, query: &str) {
+    let path_and_query = match query.is_empty() {
+        true => req.path().to_string(),
+        false => format!("{}?{query}", req.path())
+    };
+
+    let mut parts = req
+        .uri()
+        .clone()
+        .into_parts();
+
+    parts.path_and_query = PathAndQuery::from_str(&path_and_query).ok();
+
+    if let Ok(uri) = Uri::from_parts(parts) {
+        req.match_info_mut().get_mut().update(&uri);
+        req.head_mut().uri = uri;
+    }
+}
+
+// strings are written without quotes, anything that isn't a scalar as compact json.
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        value => value.to_string()
+    }
+}
+
+// lists are written one item per line.
+fn render_text(value: &Value) -> String {
+    let text = match value {
+        Value::Array(items) => items
+            .iter()
+            .map(scalar_text)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        value => scalar_text(value)
+    };
+
+    text + "\n"
+}
+
+fn csv_field(value: &Value) -> String {
+    let text = scalar_text(value);
+
+    match text.contains([',', '"', '\r', '\n']) {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => text
+    }
+}
+
+// lists are written one item per row, objects get a header row with the
+// keys of the first one.
+fn render_csv(value: &Value) -> String {
+    let rows = match value {
+        Value::Array(items) => items.iter().collect::<Vec<_>>(),
+        value => vec![value]
+    };
+
+    let header = match rows.first() {
+        Some(Value::Object(first)) => first.keys().cloned().collect::<Vec<_>>(),
+        _ => Vec::new()
+    };
+
+    let mut lines = Vec::new();
+
+    if !header.is_empty() {
+        lines.push(
+            header
+                .iter()
+                .map(|key| csv_field(&Value::String(key.clone())))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+    }
+
+    for row in rows {
+        let fields = match row {
+            Value::Object(fields) if !header.is_empty() => header
+                .iter()
+                .map(|key| fields.get(key).map(csv_field).unwrap_or_default())
+                .collect::<Vec<_>>(),
+            value => vec![csv_field(value)]
+        };
+
+        lines.push(fields.join(","));
+    }
+
+    lines.join("\r\n") + "\r\n"
+}
@@ -0,0 +1,34 @@
+use std::{future::{ready, Ready}, ops::Deref};
+use actix_web::{dev::Payload, web::Query, Error as ActixWebError, FromRequest, HttpRequest};
+use serde::de::DeserializeOwned;
+use super::errors::ApiError;
+
+// a query where `+` is kept as is instead of being decoded as a space,
+// for parameters carrying offsets such as +02:00.
+pub struct LiteralQuery<T>(pub T);
+
+impl<T> Deref for LiteralQuery<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: DeserializeOwned> FromRequest for LiteralQuery<T> {
+    type Error = ActixWebError;
+
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let query = req
+            .query_string()
+            .replace('+', "%2B");
+
+        ready(
+            Query::<T>::from_query(&query)
+                .map(|query| Self(query.into_inner()))
+                .map_err(|error| ApiError::bad_request("invalid_query", error).into())
+        )
+    }
+}
@@ -1,35 +1,109 @@
-use actix_web::{body::BoxBody, http::header::ContentType, HttpResponse};
-use serde::Serialize;
-use serde_json::to_string;
+use actix_web::{body::BoxBody, http::header::VARY, HttpResponse};
+use serde::{Deserialize, Serialize};
+use serde_json::{to_value, Error as JsonError};
 use time::OffsetDateTime;
-use crate::models::key::ApiKey;
+use utoipa::ToSchema;
+use crate::{helpers::{authentication::signing::{next_serial, sign, verify, verifying_key, SigningError}, http::{context::{current_format, with_current, PendingReceipt}, errors::ApiError}}, models::{key::ApiKey, receipt::{generate_receipt_id, receipts_enabled}}};
 
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct TimedResponse<T: Serialize> {
+    #[serde(skip)]
+    key_id: Option<i32>,
+    #[serde(skip)]
+    receipt_required: bool,
     author: Option<i32>,
     timestamp: i64,
-    value: T
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    receipt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    serial: Option<u64>,
+    value: T,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    signature: Option<String>
 }
 
 impl<T: Serialize> TimedResponse<T> {
     pub fn new(value: T, key: Option<ApiKey>) -> Self {
         Self {
+            key_id: key.as_ref().map(|key| key.id),
+            receipt_required: false,
             author: key.map(|key| key.user_id),
             timestamp: OffsetDateTime::now_utc().unix_timestamp(),
-            value
+            receipt: None,
+            serial: None,
+            value,
+            signature: None
+        }
+    }
+
+    // issues a receipt even for anonymous requests when receipts are disabled for them.
+    pub fn require_receipt(mut self) -> Self {
+        self.receipt_required = true;
+        self
+    }
+
+    // the receipt is stored by the request context once the response is sent.
+    fn with_receipt(mut self) -> Result<Self, JsonError> {
+        if !self.receipt_required && !receipts_enabled(self.key_id) {
+            return Ok(self);
+        }
+
+        let pending = PendingReceipt {
+            id: generate_receipt_id(),
+            key_id: self.key_id,
+            value: to_value(&self.value)?,
+            timestamp: self.timestamp
+        };
+
+        let id = pending.id.clone();
+
+        if with_current(|context| context.receipt = Some(pending)).is_some() {
+            self.receipt = Some(id);
+        }
+
+        Ok(self)
+    }
+
+    // the signature covers the response without the signature field.
+    fn signed(mut self) -> Result<Self, SigningError> {
+        if verifying_key().is_none() {
+            return Ok(self);
+        }
+
+        self.serial = Some(next_serial());
+        self.signature = sign(&self)?;
+
+        Ok(self)
+    }
+
+    pub fn verify(mut self) -> Result<bool, SigningError> {
+        match (self.signature.take(), self.serial) {
+            (Some(signature), Some(_)) => verify(&self, &signature),
+            _ => Ok(false)
         }
     }
 }
 
+// the body follows the format negotiated for the request, the signature
+// still covers the json serialization.
 impl<T: Serialize> From<TimedResponse<T>> for HttpResponse<BoxBody> {
     fn from(val: TimedResponse<T>) -> Self {
-        match to_string(&val) {
-            Ok(json) => HttpResponse::Ok()
-                .content_type(ContentType::json())
-                .body(json),
-            Err(error) => HttpResponse::InternalServerError()
-                .content_type(ContentType::plaintext())
-                .body(format!("{error:#}")),
+        let format = current_format();
+
+        let body = val
+            .with_receipt()
+            .map_err(SigningError::from)
+            .and_then(TimedResponse::signed)
+            .map_err(ApiError::internal)
+            .and_then(|val| format.encode(&val, &val.value).map_err(ApiError::internal));
+
+        match body {
+            Ok(body) => HttpResponse::Ok()
+                .content_type(format.content_type())
+                .insert_header((VARY, "Accept"))
+                .body(body),
+            Err(error) => error
+                .into()
         }
     }
 }
@@ -0,0 +1,151 @@
+use std::{fs::File, io::{BufReader, Error as IoError}, net::SocketAddr, path::{Path, PathBuf}, sync::{Arc, RwLock}};
+use actix_web::{dev::Server, http::{header::LOCATION, uri::Authority}, web::to, App, HttpRequest, HttpResponse, HttpServer};
+use log::{info, warn};
+use rustls::{server::{ClientHello, ResolvesServerCert}, sign::{any_supported_type, CertifiedKey, SignError}, Certificate, PrivateKey, ServerConfig};
+use rustls_pemfile::{certs, read_one, Item};
+use thiserror::Error;
+use tokio::signal::unix::{signal, SignalKind};
+use crate::helpers::misc::config::TlsConfig;
+
+#[derive(Debug, Error)]
+pub enum TlsError {
+    #[error("{0:#}")]
+    Io(#[from] IoError),
+
+    #[error("{0:#}")]
+    Key(#[from] SignError),
+
+    #[error("{0} doesn't contain any certificate.")]
+    NoCertificates(String),
+
+    #[error("{0} doesn't contain a private key.")]
+    NoKey(String)
+}
+
+// the certificate is swapped in place so reloading it doesn't need a restart.
+pub struct CertificateResolver {
+    certificate: PathBuf,
+    key: PathBuf,
+    current: RwLock<Arc<CertifiedKey>>
+}
+
+fn load_certified_key(certificate: &Path, key: &Path) -> Result<CertifiedKey, TlsError> {
+    let chain = certs(&mut BufReader::new(File::open(certificate)?))?
+        .into_iter()
+        .map(Certificate)
+        .collect::<Vec<_>>();
+
+    if chain.is_empty() {
+        return Err(TlsError::NoCertificates(certificate.display().to_string()));
+    }
+
+    let mut reader = BufReader::new(File::open(key)?);
+
+    let private_key = loop {
+        match read_one(&mut reader)? {
+            Some(Item::RSAKey(key) | Item::PKCS8Key(key) | Item::ECKey(key)) => break PrivateKey(key),
+            Some(_) => continue,
+            None => return Err(TlsError::NoKey(key.display().to_string()))
+        }
+    };
+
+    Ok(CertifiedKey::new(chain, any_supported_type(&private_key)?))
+}
+
+impl CertificateResolver {
+    pub fn load(config: &TlsConfig) -> Result<Arc<Self>, TlsError> {
+        let current = load_certified_key(&config.certificate, &config.key)?;
+
+        Ok(Arc::new(Self {
+            certificate: config.certificate.clone(),
+            key: config.key.clone(),
+            current: RwLock::new(Arc::new(current))
+        }))
+    }
+
+    fn reload(&self) -> Result<(), TlsError> {
+        let reloaded = load_certified_key(&self.certificate, &self.key)?;
+
+        *self.current
+            .write()
+            .unwrap() = Arc::new(reloaded);
+
+        Ok(())
+    }
+
+    pub fn server_config(self: &Arc<Self>) -> ServerConfig {
+        ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_cert_resolver(self.clone())
+    }
+}
+
+impl ResolvesServerCert for CertificateResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(self.current
+            .read()
+            .unwrap()
+            .clone())
+    }
+}
+
+// a certificate that fails to load keeps the previous one in use.
+pub async fn reload_on_hangup(resolver: Arc<CertificateResolver>) {
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(hangups) => hangups,
+        Err(error) => {
+            warn!("Couldn't listen for SIGHUP, certificates won't be reloaded: {error:#}");
+            return;
+        }
+    };
+
+    while hangups.recv().await.is_some() {
+        match resolver.reload() {
+            Ok(()) => info!("Reloaded the TLS certificate."),
+            Err(error) => warn!("Couldn't reload the TLS certificate, keeping the current one: {error:#}")
+        }
+    }
+}
+
+pub fn hsts_header(max_age: u64) -> String {
+    format!("max-age={max_age}; includeSubDomains")
+}
+
+// permanent redirects keep the method and body of the request.
+async fn redirect_to_https(req: HttpRequest, https_port: u16) -> HttpResponse {
+    let host = req
+        .connection_info()
+        .host()
+        .parse::<Authority>()
+        .map(|authority| authority.host().to_string())
+        .unwrap_or_default();
+
+    let path = req
+        .uri()
+        .path_and_query()
+        .map_or("/", |path| path.as_str());
+
+    let location = match https_port {
+        443 => format!("https://{host}{path}"),
+        port => format!("https://{host}:{port}{path}")
+    };
+
+    HttpResponse::PermanentRedirect()
+        .insert_header((LOCATION, location))
+        .finish()
+}
+
+pub fn redirect_server(address: SocketAddr, https_port: u16) -> Result<Server, IoError> {
+    info!("Redirecting http requests on {address} to https.");
+
+    Ok(
+        HttpServer::new(move || {
+            App::new()
+                .default_service(to(move |req: HttpRequest| redirect_to_https(req, https_port)))
+        })
+            .workers(1)
+            .bind(address)?
+            .run()
+    )
+}
@@ -0,0 +1,32 @@
+use actix_web::{body::MessageBody, dev::{ServiceRequest, ServiceResponse}, http::header::{HeaderName, HeaderValue}, middleware::Next, Error as ActixWebError};
+
+// when the unversioned aliases were deprecated and when they will be
+// removed, as an RFC 9745 date and an HTTP date respectively.
+const ALIAS_DEPRECATION: &str = "@1792368000";
+const ALIAS_SUNSET: &str = "Mon, 19 Apr 2027 00:00:00 GMT";
+
+// the version the unversioned aliases point to.
+pub const CURRENT_VERSION: &str = "v1";
+
+pub async fn deprecated_alias(req: ServiceRequest, next: Next<impl MessageBody>)
+    -> Result<ServiceResponse<impl MessageBody>, ActixWebError>
+{
+    // the alias can be mounted under a prefix, the successor keeps it.
+    let path = req.path();
+    let alias = req.match_info().unprocessed();
+    let prefix = &path[..path.len() - alias.len()];
+
+    let successor = format!("<{prefix}/{CURRENT_VERSION}{alias}>; rel=\"successor-version\"");
+
+    let mut response = next.call(req).await?;
+    let headers = response.headers_mut();
+
+    headers.insert(HeaderName::from_static("deprecation"), HeaderValue::from_static(ALIAS_DEPRECATION));
+    headers.insert(HeaderName::from_static("sunset"), HeaderValue::from_static(ALIAS_SUNSET));
+
+    if let Ok(successor) = HeaderValue::from_str(&successor) {
+        headers.insert(HeaderName::from_static("link"), successor);
+    }
+
+    Ok(response)
+}
@@ -0,0 +1,19 @@
+use std::env::args;
+
+
+pub fn arg_values(name: &str) -> Vec<String> {
+    args()
+        .zip(args().skip(1))
+        .filter(|(arg, _)| arg == name)
+        .map(|(_, val)| val)
+        .collect()
+}
+
+pub fn arg_value(name: &str) -> Option<String> {
+    arg_values(name)
+        .pop()
+}
+
+pub fn arg_flag(name: &str) -> bool {
+    args().any(|arg| arg == name)
+}
@@ -1,27 +1,306 @@
-use serde::Serialize;
+use std::str::FromStr;
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+use utoipa::ToSchema;
 
 
-#[derive(Serialize)]
+#[derive(Debug, Error)]
+pub enum ColorError {
+    #[error("Invalid color `{0}`, expected an hexadecimal color such as #ff8800.")]
+    InvalidColor(String),
+
+    #[error("Invalid format `{0}`, expected one of rgb, hex, css, hsl, hsv or cmyk.")]
+    InvalidFormat(String),
+
+    #[error("Invalid scheme `{0}`, expected one of complementary, triadic or analogous.")]
+    InvalidScheme(String),
+
+    #[error("A contrast of {0} can't be reached against the provided background.")]
+    UnreachableContrast(f64)
+}
+
+#[derive(Serialize, ToSchema, Clone, Copy)]
 pub struct Color {
     red: u8,
     green: u8,
-    blue: u8
+    blue: u8,
+    #[serde(skip_serializing_if = "Option::is_none", serialize_with = "alpha_fraction")]
+    #[schema(value_type = Option<f64>)]
+    alpha: Option<u8>
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Hsl {
+    hue: f64,
+    saturation: f64,
+    lightness: f64
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Hsv {
+    hue: f64,
+    saturation: f64,
+    value: f64
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Cmyk {
+    cyan: f64,
+    magenta: f64,
+    yellow: f64,
+    key: f64
+}
+
+#[derive(Clone, Copy)]
+pub enum ColorFormat {
+    Rgb,
+    Hex,
+    Css,
+    Hsl,
+    Hsv,
+    Cmyk
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum FormattedColor {
+    Rgb(Color),
+    Hex(String),
+    Css(String),
+    Hsl(Hsl),
+    Hsv(Hsv),
+    Cmyk(Cmyk)
+}
+
+#[derive(Clone, Copy)]
+pub enum PaletteScheme {
+    Complementary,
+    Triadic,
+    Analogous
+}
+
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+// the alpha byte is kept as is, it only becomes a fraction when displayed.
+fn alpha_fraction<S: Serializer>(alpha: &Option<u8>, serializer: S) -> Result<S::Ok, S::Error> {
+    match alpha {
+        Some(alpha) => serializer.serialize_some(&round(*alpha as f64 / 255.0)),
+        None => serializer.serialize_none()
+    }
+}
+
+fn hue_of(red: f64, green: f64, blue: f64, max: f64, delta: f64) -> f64 {
+    if delta == 0.0 {
+        return 0.0;
+    }
+
+    let hue = if max == red {
+        ((green - blue) / delta).rem_euclid(6.0)
+    } else if max == green {
+        (blue - red) / delta + 2.0
+    } else {
+        (red - green) / delta + 4.0
+    };
+
+    hue * 60.0
 }
 
 impl Color {
-    #[allow(unused)] // may be used in a future.
     pub fn new(red: u8, green: u8, blue: u8) -> Self {
         Self {
             red,
             green,
-            blue
+            blue,
+            alpha: None
+        }
+    }
+
+    pub fn with_alpha(mut self, alpha: u8) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
+    fn channels(&self) -> (f64, f64, f64) {
+        (
+            self.red as f64 / 255.0,
+            self.green as f64 / 255.0,
+            self.blue as f64 / 255.0
+        )
+    }
+
+    pub fn as_hex(&self) -> String {
+        let rgb = format!("#{:02x}{:02x}{:02x}", self.red, self.green, self.blue);
+
+        match self.alpha {
+            Some(alpha) => format!("{rgb}{alpha:02x}"),
+            None => rgb
+        }
+    }
+
+    pub fn as_css(&self) -> String {
+        match self.alpha {
+            Some(alpha) => format!(
+                "rgb({} {} {} / {})",
+                self.red,
+                self.green,
+                self.blue,
+                round(alpha as f64 / 255.0)
+            ),
+            None => format!("rgb({} {} {})", self.red, self.green, self.blue)
+        }
+    }
+
+    pub fn as_hsl(&self) -> Hsl {
+        let (red, green, blue) = self.channels();
+        let max = red.max(green).max(blue);
+        let min = red.min(green).min(blue);
+        let delta = max - min;
+        let lightness = (max + min) / 2.0;
+
+        let saturation = if delta == 0.0 {
+            0.0
+        } else {
+            delta / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+
+        Hsl {
+            hue: round(hue_of(red, green, blue, max, delta)),
+            saturation: round(saturation * 100.0),
+            lightness: round(lightness * 100.0)
+        }
+    }
+
+    pub fn as_hsv(&self) -> Hsv {
+        let (red, green, blue) = self.channels();
+        let max = red.max(green).max(blue);
+        let delta = max - red.min(green).min(blue);
+
+        Hsv {
+            hue: round(hue_of(red, green, blue, max, delta)),
+            saturation: round(if max == 0.0 { 0.0 } else { delta / max * 100.0 }),
+            value: round(max * 100.0)
+        }
+    }
+
+    pub fn as_cmyk(&self) -> Cmyk {
+        let (red, green, blue) = self.channels();
+        let key = 1.0 - red.max(green).max(blue);
+
+        let ink = |channel: f64| if key == 1.0 {
+            0.0
+        } else {
+            round((1.0 - channel - key) / (1.0 - key) * 100.0)
+        };
+
+        Cmyk {
+            cyan: ink(red),
+            magenta: ink(green),
+            yellow: ink(blue),
+            key: round(key * 100.0)
         }
     }
 
-    pub fn as_hex(&self) -> u32 {
-        ((self.red as u32) << 16)
-        | ((self.green as u32) << 8)
-        | self.blue as u32
+    pub fn from_hsl(Hsl { hue, saturation, lightness }: Hsl) -> Self {
+        let saturation = saturation.clamp(0.0, 100.0) / 100.0;
+        let lightness = lightness.clamp(0.0, 100.0) / 100.0;
+        let hue = hue.rem_euclid(360.0) / 60.0;
+
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let second = chroma * (1.0 - (hue % 2.0 - 1.0).abs());
+        let offset = lightness - chroma / 2.0;
+
+        let (red, green, blue) = match hue as u8 {
+            0 => (chroma, second, 0.0),
+            1 => (second, chroma, 0.0),
+            2 => (0.0, chroma, second),
+            3 => (0.0, second, chroma),
+            4 => (second, 0.0, chroma),
+            _ => (chroma, 0.0, second)
+        };
+
+        let channel = |value: f64| ((value + offset) * 255.0).round() as u8;
+
+        Self::new(channel(red), channel(green), channel(blue))
+    }
+
+    pub fn formatted(self, format: ColorFormat) -> FormattedColor {
+        match format {
+            ColorFormat::Rgb => FormattedColor::Rgb(self),
+            ColorFormat::Hex => FormattedColor::Hex(self.as_hex()),
+            ColorFormat::Css => FormattedColor::Css(self.as_css()),
+            ColorFormat::Hsl => FormattedColor::Hsl(self.as_hsl()),
+            ColorFormat::Hsv => FormattedColor::Hsv(self.as_hsv()),
+            ColorFormat::Cmyk => FormattedColor::Cmyk(self.as_cmyk())
+        }
+    }
+
+    pub fn luminance(&self) -> f64 {
+        let linear = |channel: f64| if channel <= 0.03928 {
+            channel / 12.92
+        } else {
+            ((channel + 0.055) / 1.055).powf(2.4)
+        };
+
+        let (red, green, blue) = self.channels();
+
+        0.2126 * linear(red) + 0.7152 * linear(green) + 0.0722 * linear(blue)
+    }
+
+    pub fn contrast(&self, other: &Color) -> f64 {
+        let (first, second) = (self.luminance(), other.luminance());
+
+        (first.max(second) + 0.05) / (first.min(second) + 0.05)
+    }
+
+    pub fn with_contrast(self, background: &Color, minimum: f64) -> Result<Self, ColorError> {
+        if self.contrast(background) >= minimum {
+            return Ok(self);
+        }
+
+        let Hsl { hue, saturation, lightness } = self.as_hsl();
+
+        // try the closest lightness values first, keeping the hue so
+        // the color still looks related to the original one.
+        (0..=100)
+            .flat_map(|step| [lightness + step as f64, lightness - step as f64])
+            .filter(|lightness| (0.0..=100.0).contains(lightness))
+            .chain([0.0, 100.0])
+            .map(|lightness| Self {
+                alpha: self.alpha,
+                ..Self::from_hsl(Hsl { hue, saturation, lightness })
+            })
+            .find(|color| color.contrast(background) >= minimum)
+            .ok_or(ColorError::UnreachableContrast(minimum))
+    }
+
+    pub fn palette(self, scheme: PaletteScheme, size: usize) -> Vec<Self> {
+        let offsets: &[f64] = match scheme {
+            PaletteScheme::Complementary => &[0.0, 180.0],
+            PaletteScheme::Triadic => &[0.0, 120.0, 240.0],
+            PaletteScheme::Analogous => &[0.0, 30.0, -30.0, 60.0, -60.0]
+        };
+
+        let Hsl { hue, saturation, lightness } = self.as_hsl();
+
+        (0..size)
+            .map(|index| {
+                // once every hue of the scheme is used the following
+                // rounds shift the lightness to keep the colors distinct.
+                let round = (index / offsets.len()) as f64;
+                let lightness = 15.0 + (lightness - 15.0 + round * 20.0).rem_euclid(70.0);
+
+                Self {
+                    alpha: self.alpha,
+                    ..Self::from_hsl(Hsl {
+                        hue: hue + offsets[index % offsets.len()],
+                        saturation,
+                        lightness
+                    })
+                }
+            })
+            .collect()
     }
 }
 
@@ -30,7 +309,73 @@ impl From<u32> for Color {
         Self {
             red: ((value >> 16) & 0xFF) as u8,
             green: ((value >> 8) & 0xFF) as u8,
-            blue: (value & 0xFF) as u8
+            blue: (value & 0xFF) as u8,
+            alpha: None
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = ColorError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let hex = value.trim_start_matches('#');
+
+        let hex = match hex.len() {
+            3 => hex
+                .chars()
+                .flat_map(|c| [c, c])
+                .collect::<String>(),
+            6 => hex.to_string(),
+            _ => return Err(ColorError::InvalidColor(value.to_string()))
+        };
+
+        // from_str_radix would take a leading sign as well.
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(ColorError::InvalidColor(value.to_string()));
+        }
+
+        u32::from_str_radix(&hex, 16)
+            .map(Self::from)
+            .map_err(|_| ColorError::InvalidColor(value.to_string()))
+    }
+}
+
+impl FromStr for ColorFormat {
+    type Err = ColorError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "rgb" => Ok(Self::Rgb),
+            "hex" => Ok(Self::Hex),
+            "css" => Ok(Self::Css),
+            "hsl" => Ok(Self::Hsl),
+            "hsv" => Ok(Self::Hsv),
+            "cmyk" => Ok(Self::Cmyk),
+            _ => Err(ColorError::InvalidFormat(value.to_string()))
+        }
+    }
+}
+
+impl FromStr for PaletteScheme {
+    type Err = ColorError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "complementary" => Ok(Self::Complementary),
+            "triadic" => Ok(Self::Triadic),
+            "analogous" => Ok(Self::Analogous),
+            _ => Err(ColorError::InvalidScheme(value.to_string()))
+        }
+    }
+}
+
+impl PaletteScheme {
+    pub fn default_size(&self) -> usize {
+        match self {
+            Self::Complementary => 2,
+            Self::Triadic => 3,
+            Self::Analogous => 5
         }
     }
 }
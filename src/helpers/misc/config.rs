@@ -0,0 +1,512 @@
+use std::{env::var, fmt::{Display, Formatter, Result as FmtResult}, fs::read_to_string, io::Error as IoError, net::SocketAddr, path::{Path, PathBuf}, str::FromStr, sync::OnceLock};
+use flexi_logger::LogSpecification;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+use toml::{de::Error as TomlError, Table, Value};
+use super::args::{arg_flag, arg_value, arg_values};
+
+const DEFAULT_PATH: &str = "rlarndg.toml";
+const DEFAULT_BIND: &str = "127.0.0.1:5174";
+const DEFAULT_MAX_CONNECTIONS: u32 = 5;
+const DEFAULT_ANONYMOUS_INTERVAL: u64 = 30;
+const DEFAULT_LOG_LEVEL: &str = "debug";
+const MIN_JWT_SECRET_LENGTH: usize = 32;
+const DEFAULT_HSTS_MAX_AGE: u64 = 31_536_000;
+const DEFAULT_ARCHIVE_RETENTION_DAYS: u64 = 7;
+const DEFAULT_ARCHIVE_MAX_BYTES: u64 = 1 << 30;
+const DEFAULT_DRAND_PERIOD: u64 = 30;
+const DEFAULT_RECEIPT_RETENTION_DAYS: i32 = 30;
+#[cfg(feature = "grpc")]
+const DEFAULT_GRPC_BIND: &str = "127.0.0.1:5175";
+
+// every key the file accepts, the environment variable of a key is its
+// name in uppercase with dots replaced by underscores and an RLARNDG_ prefix.
+const KEYS: [&str; 24] = [
+    "sources",
+    "server.bind",
+    "server.workers",
+    "database.url",
+    "database.max_connections",
+    "secrets.stripe",
+    "secrets.jwt",
+    "rate_limit.anonymous_interval",
+    "logging.level",
+    "tls.certificate",
+    "tls.key",
+    "tls.redirect",
+    "tls.hsts_max_age",
+    "frontend.path",
+    "archive.directory",
+    "archive.retention_days",
+    "archive.max_bytes",
+    "beacon.interval",
+    "drand.genesis",
+    "drand.period",
+    "signing.key",
+    "receipts.retention_days",
+    "receipts.anonymous",
+    "grpc.bind"
+];
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("Couldn't read {0}: {1:#}")]
+    Read(String, IoError),
+
+    #[error("Couldn't parse {0}: {1}")]
+    Parse(String, TomlError),
+
+    #[error("Unknown setting `{0}` in {1}.")]
+    Unknown(String, String),
+
+    #[error("Invalid `{key}` from {origin}: {message}")]
+    Invalid { key: &'static str, origin: String, message: String },
+
+    #[error("Missing `{0}`, set it in the config file, {1}.")]
+    Missing(&'static str, String)
+}
+
+#[derive(Debug)]
+pub struct ConfigErrors(Vec<ConfigError>);
+
+pub struct ServerConfig {
+    pub bind: SocketAddr,
+    pub workers: Option<usize>
+}
+
+pub struct DatabaseConfig {
+    pub url: String,
+    pub max_connections: u32
+}
+
+pub struct SecretsConfig {
+    pub stripe: String,
+    pub jwt: Option<String>
+}
+
+pub struct RateLimitConfig {
+    pub anonymous_interval: u64
+}
+
+pub struct LoggingConfig {
+    pub level: String
+}
+
+pub struct TlsConfig {
+    pub certificate: PathBuf,
+    pub key: PathBuf,
+    pub redirect: Option<SocketAddr>,
+    pub hsts_max_age: u64
+}
+
+pub struct FrontendConfig {
+    pub path: Option<PathBuf>
+}
+
+pub struct ArchiveConfig {
+    pub directory: PathBuf,
+    pub retention_days: u64,
+    pub max_bytes: u64
+}
+
+pub struct BeaconConfig {
+    pub interval: Option<u64>
+}
+
+pub struct DrandConfig {
+    pub genesis: Option<i64>,
+    pub period: u64
+}
+
+pub struct SigningConfig {
+    pub key: Option<PathBuf>
+}
+
+pub struct ReceiptsConfig {
+    pub retention_days: i32,
+    pub anonymous: bool
+}
+
+#[cfg(feature = "grpc")]
+pub struct GrpcConfig {
+    pub bind: SocketAddr
+}
+
+pub struct Config {
+    pub sources: Vec<PathBuf>,
+    pub server: ServerConfig,
+    pub database: DatabaseConfig,
+    pub secrets: SecretsConfig,
+    pub rate_limit: RateLimitConfig,
+    pub logging: LoggingConfig,
+    pub tls: Option<TlsConfig>,
+    pub frontend: FrontendConfig,
+    pub archive: Option<ArchiveConfig>,
+    pub beacon: BeaconConfig,
+    pub drand: DrandConfig,
+    pub signing: SigningConfig,
+    pub receipts: ReceiptsConfig,
+    #[cfg(feature = "grpc")]
+    pub grpc: GrpcConfig
+}
+
+struct Loader {
+    file: String,
+    table: Table,
+    errors: Vec<ConfigError>
+}
+
+impl Display for ConfigErrors {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        writeln!(f, "The configuration is invalid:")?;
+
+        for error in &self.0 {
+            writeln!(f, "  - {error}")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn env_name(key: &str) -> String {
+    format!("RLARNDG_{}", key.replace('.', "_").to_uppercase())
+}
+
+fn check_file(path: &Path) -> Result<(), String> {
+    match path.is_file() {
+        true => Ok(()),
+        false => Err(format!("{} is not a file", path.display()))
+    }
+}
+
+fn check_directory(path: &Path) -> Result<(), String> {
+    match path.join("index.html").is_file() {
+        true => Ok(()),
+        false => Err(format!("{} doesn't contain an index.html", path.display()))
+    }
+}
+
+fn check_positive<T: PartialOrd + Default>(value: &T) -> Result<(), String> {
+    match *value > T::default() {
+        true => Ok(()),
+        false => Err("expected a value greater than 0".into())
+    }
+}
+
+impl Loader {
+    // a missing file is only an error when its path was given explicitly.
+    fn new() -> Self {
+        let explicit = arg_value("--config")
+            .or_else(|| var(env_name("config")).ok());
+
+        let file = explicit
+            .clone()
+            .unwrap_or(DEFAULT_PATH.into());
+
+        let mut loader = Self {
+            file: file.clone(),
+            table: Table::new(),
+            errors: Vec::new()
+        };
+
+        if explicit.is_none() && !Path::new(&file).exists() {
+            return loader;
+        }
+
+        match read_to_string(&file).map(|text| text.parse::<Table>()) {
+            Ok(Ok(table)) => loader.table = table,
+            Ok(Err(error)) => loader.errors.push(ConfigError::Parse(file, error)),
+            Err(error) => loader.errors.push(ConfigError::Read(file, error))
+        }
+
+        loader.check_unknown();
+        loader
+    }
+
+    fn check_unknown(&mut self) {
+        let mut unknown = Vec::new();
+
+        for (name, value) in &self.table {
+            match value {
+                Value::Table(section) if name != "sources" => unknown.extend(
+                    section
+                        .keys()
+                        .map(|key| format!("{name}.{key}"))
+                        .filter(|key| !KEYS.contains(&key.as_str()))
+                ),
+                _ if !KEYS.contains(&name.as_str()) => unknown.push(name.clone()),
+                _ => {}
+            }
+        }
+
+        for key in unknown {
+            self.errors.push(ConfigError::Unknown(key, self.file.clone()));
+        }
+    }
+
+    fn file_value(&self, key: &str) -> Option<&Value> {
+        match key.split_once('.') {
+            Some((section, name)) => self.table.get(section)?.get(name),
+            None => self.table.get(key)
+        }
+    }
+
+    fn invalid(&mut self, key: &'static str, origin: String, message: impl Display) {
+        self.errors.push(ConfigError::Invalid {
+            key,
+            origin,
+            message: message.to_string()
+        });
+    }
+
+    // flags win over the environment, which wins over the file.
+    fn value<T>(&mut self, key: &'static str, flag: Option<&str>, check: impl FnOnce(&T) -> Result<(), String>) -> Option<T>
+    where
+        T: FromStr + DeserializeOwned,
+        T::Err: Display
+    {
+        let env = env_name(key);
+
+        let (origin, parsed) = if let Some((flag, value)) = flag.and_then(|flag| Some((flag, arg_value(flag)?))) {
+            (format!("`{flag}`"), value.parse::<T>().map_err(|error| error.to_string()))
+        } else if let Ok(value) = var(&env) {
+            (env, value.parse::<T>().map_err(|error| error.to_string()))
+        } else {
+            let value = self.file_value(key)?.clone();
+            (self.file.clone(), value.try_into::<T>().map_err(|error| error.message().to_string()))
+        };
+
+        match parsed.and_then(|value| check(&value).map(|_| value)) {
+            Ok(value) => Some(value),
+            Err(message) => {
+                self.invalid(key, origin, message);
+                None
+            }
+        }
+    }
+
+    fn required<T>(&mut self, key: &'static str, flag: Option<&str>, check: impl FnOnce(&T) -> Result<(), String>) -> Option<T>
+    where
+        T: FromStr + DeserializeOwned,
+        T::Err: Display
+    {
+        let value = self.value(key, flag, check);
+
+        if value.is_none() {
+            self.missing(key, flag);
+        }
+
+        value
+    }
+
+    // a setting that was set but is invalid is only reported once.
+    fn missing(&mut self, key: &'static str, flag: Option<&str>) {
+        let invalid = self.errors
+            .iter()
+            .any(|error| matches!(error, ConfigError::Invalid { key: invalid, .. } if *invalid == key));
+
+        if invalid {
+            return;
+        }
+
+        let hint = match flag {
+            Some(flag) => format!("as {} or with `{flag}`", env_name(key)),
+            None => format!("or as {}", env_name(key))
+        };
+
+        self.errors.push(ConfigError::Missing(key, hint));
+    }
+
+    // sources can be repeated as flags or separated by commas in the environment.
+    fn sources(&mut self) -> Vec<PathBuf> {
+        let key = "sources";
+        let env = env_name(key);
+        let flags = arg_values("--source");
+
+        let (origin, sources) = if !flags.is_empty() {
+            ("`--source`".to_string(), flags.into_iter().map(PathBuf::from).collect())
+        } else if let Ok(value) = var(&env) {
+            (env, value.split(',').map(str::trim).filter(|path| !path.is_empty()).map(PathBuf::from).collect())
+        } else {
+            match self.file_value(key).cloned().map(Value::try_into::<Vec<PathBuf>>) {
+                Some(Ok(sources)) => (self.file.clone(), sources),
+                Some(Err(error)) => {
+                    self.invalid(key, self.file.clone(), error.message());
+                    return Vec::new();
+                },
+                None => return Vec::new()
+            }
+        };
+
+        for source in &sources {
+            if !source.is_file() {
+                self.invalid(key, origin.clone(), format!("{} is not a file", source.display()));
+            }
+        }
+
+        sources
+    }
+}
+
+impl Config {
+    // every setting is read before failing so all the errors are reported at once.
+    fn load() -> Result<Self, ConfigErrors> {
+        let mut loader = Loader::new();
+
+        let sources = loader.sources();
+
+        let bind = loader.value("server.bind", Some("--bind"), |_| Ok(()));
+        let workers = loader.value("server.workers", Some("--workers"), check_positive);
+
+        let database_url = loader.required("database.url", Some("--database-url"), |url: &String| {
+            match url.starts_with("postgres://") || url.starts_with("postgresql://") {
+                true => Ok(()),
+                false => Err("expected a postgres:// url".into())
+            }
+        });
+
+        let max_connections = loader.value("database.max_connections", Some("--database-max-connections"), check_positive);
+
+        // secrets aren't read from flags so they don't show up in the process list.
+        let stripe = loader.required("secrets.stripe", None, |secret: &String| {
+            match secret.is_empty() {
+                true => Err("expected a non empty secret".into()),
+                false => Ok(())
+            }
+        });
+
+        let jwt = loader.value("secrets.jwt", None, |secret: &String| {
+            match secret.len() >= MIN_JWT_SECRET_LENGTH {
+                true => Ok(()),
+                false => Err(format!("expected at least {MIN_JWT_SECRET_LENGTH} characters"))
+            }
+        });
+
+        let anonymous_interval = loader.value("rate_limit.anonymous_interval", Some("--anonymous-interval"), |_| Ok(()));
+
+        let level = loader.value("logging.level", Some("--log-level"), |level: &String| {
+            LogSpecification::parse(level)
+                .map(|_| ())
+                .map_err(|error| error.to_string())
+        });
+
+        let certificate = loader.value("tls.certificate", Some("--tls-certificate"), |path: &PathBuf| check_file(path));
+        let key = loader.value("tls.key", Some("--tls-key"), |path: &PathBuf| check_file(path));
+        let redirect = loader.value("tls.redirect", Some("--tls-redirect"), |_| Ok(()));
+        let hsts_max_age = loader.value("tls.hsts_max_age", Some("--hsts-max-age"), |_| Ok(()));
+
+        // tls is enabled by the certificate and its key, the other settings only apply with it.
+        let tls = match (certificate, key) {
+            (Some(certificate), Some(key)) => Some(TlsConfig {
+                certificate,
+                key,
+                redirect,
+                hsts_max_age: hsts_max_age.unwrap_or(DEFAULT_HSTS_MAX_AGE)
+            }),
+            (Some(_), None) => {
+                loader.missing("tls.key", Some("--tls-key"));
+                None
+            },
+            (None, Some(_)) => {
+                loader.missing("tls.certificate", Some("--tls-certificate"));
+                None
+            },
+            (None, None) => None
+        };
+
+        let frontend = loader.value("frontend.path", Some("--frontend"), |path: &PathBuf| check_directory(path));
+
+        // the archive is enabled by its directory, like tls by its certificate.
+        let archive_directory = loader.value("archive.directory", Some("--archive-dir"), |_| Ok(()));
+        let archive_retention_days = loader.value("archive.retention_days", Some("--archive-retention-days"), check_positive);
+        let archive_max_bytes = loader.value("archive.max_bytes", Some("--archive-max-bytes"), check_positive);
+
+        let beacon_interval = loader.value("beacon.interval", Some("--beacon-interval"), check_positive);
+
+        let drand_genesis = loader.value("drand.genesis", Some("--drand-genesis"), |_| Ok(()));
+        let drand_period = loader.value("drand.period", Some("--drand-period"), check_positive);
+
+        let signing_key = loader.value("signing.key", Some("--signing-key"), |_| Ok(()));
+
+        let receipt_retention_days = loader.value("receipts.retention_days", Some("--receipt-retention-days"), |days: &i32| {
+            match *days >= 0 {
+                true => Ok(()),
+                false => Err("expected 0 to keep receipts forever or a number of days".into())
+            }
+        });
+
+        // a switch rather than a value, the flag turns it on over the file and environment.
+        let anonymous_receipts = loader.value("receipts.anonymous", None, |_| Ok(()));
+
+        #[cfg(feature = "grpc")]
+        let grpc_bind = loader.value("grpc.bind", Some("--grpc-bind"), |_| Ok(()));
+
+        if !loader.errors.is_empty() {
+            return Err(ConfigErrors(loader.errors));
+        }
+
+        Ok(Self {
+            sources,
+            server: ServerConfig {
+                bind: bind.unwrap_or_else(|| DEFAULT_BIND.parse().unwrap()),
+                workers
+            },
+            database: DatabaseConfig {
+                url: database_url.unwrap_or_default(),
+                max_connections: max_connections.unwrap_or(DEFAULT_MAX_CONNECTIONS)
+            },
+            secrets: SecretsConfig {
+                stripe: stripe.unwrap_or_default(),
+                jwt
+            },
+            rate_limit: RateLimitConfig {
+                anonymous_interval: anonymous_interval.unwrap_or(DEFAULT_ANONYMOUS_INTERVAL)
+            },
+            logging: LoggingConfig {
+                level: level.unwrap_or(DEFAULT_LOG_LEVEL.into())
+            },
+            tls,
+            frontend: FrontendConfig {
+                path: frontend
+            },
+            archive: archive_directory.map(|directory| ArchiveConfig {
+                directory,
+                retention_days: archive_retention_days.unwrap_or(DEFAULT_ARCHIVE_RETENTION_DAYS),
+                max_bytes: archive_max_bytes.unwrap_or(DEFAULT_ARCHIVE_MAX_BYTES)
+            }),
+            beacon: BeaconConfig {
+                interval: beacon_interval
+            },
+            drand: DrandConfig {
+                genesis: drand_genesis,
+                period: drand_period.unwrap_or(DEFAULT_DRAND_PERIOD)
+            },
+            signing: SigningConfig {
+                key: signing_key
+            },
+            receipts: ReceiptsConfig {
+                retention_days: receipt_retention_days.unwrap_or(DEFAULT_RECEIPT_RETENTION_DAYS),
+                anonymous: arg_flag("--anonymous-receipts") || anonymous_receipts.unwrap_or(false)
+            },
+            #[cfg(feature = "grpc")]
+            grpc: GrpcConfig {
+                bind: grpc_bind.unwrap_or_else(|| DEFAULT_GRPC_BIND.parse().unwrap())
+            }
+        })
+    }
+}
+
+pub fn init_config() -> Result<&'static Config, ConfigErrors> {
+    let config = Config::load()?;
+
+    Ok(CONFIG.get_or_init(|| config))
+}
+
+// only valid once init_config succeeded, which main does before anything else.
+pub fn config() -> &'static Config {
+    CONFIG
+        .get()
+        .expect("the configuration is loaded at startup")
+}
@@ -1,4 +1,7 @@
 
+pub mod args;
 pub mod color;
+pub mod config;
+pub mod digest;
 pub mod logging;
 pub mod stripe;
@@ -1,4 +1,3 @@
-use lazy_static::lazy_static;
 use reqwest::{header::AUTHORIZATION, Client, Error as RequestError};
 use serde::Deserialize;
 use thiserror::Error;
@@ -7,10 +6,7 @@ use crate::models::{
     key::{ApiKey, ApiKeyError},
     user::User,
 };
-
-lazy_static! {
-    static ref STRIPE_SECRET: String = lc!(env!("STRIPE_SECRET"));
-}
+use super::config::config;
 
 #[derive(Error, Debug)]
 pub enum StripeError {
@@ -33,7 +29,7 @@ pub async fn create_stripe_payment(
 ) -> Result<String, StripeError> {
     let StripeSession { url } = Client::new()
         .post("https://api.stripe.com/v1/checkout/sessions")
-        .header(AUTHORIZATION, format!("Bearer {}", *STRIPE_SECRET))
+        .header(AUTHORIZATION, format!("Bearer {}", config().secrets.stripe))
         .form(&[
             ("payment_method_types[]", "card"),
             ("line_items[0][price_data][currency]", "usd"),
@@ -50,7 +46,7 @@ pub async fn create_stripe_payment(
             (
                 "success_url",
                 &format!(
-                    "{host}/api/keys/checkout/success?i={}&p={}&c={{CHECKOUT_SESSION_ID}}",
+                    "{host}/api/v1/keys/checkout/success?i={}&p={}&c={{CHECKOUT_SESSION_ID}}",
                     user.id, amount
                 ),
             ),
@@ -83,7 +79,7 @@ pub async fn verify_payment(session_id: &String) -> Result<bool, StripeError> {
         .get(format!(
             "https://api.stripe.com/v1/checkout/sessions/{session_id}"
         ))
-        .header(AUTHORIZATION, format!("Bearer {}", *STRIPE_SECRET))
+        .header(AUTHORIZATION, format!("Bearer {}", config().secrets.stripe))
         .send()
         .await?
         .json()
@@ -93,7 +89,7 @@ pub async fn verify_payment(session_id: &String) -> Result<bool, StripeError> {
         .get(format!(
             "https://api.stripe.com/v1/payment_intents/{payment_intent}"
         ))
-        .header(AUTHORIZATION, format!("Bearer {}", *STRIPE_SECRET))
+        .header(AUTHORIZATION, format!("Bearer {}", config().secrets.stripe))
         .send()
         .await?
         .json()
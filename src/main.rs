@@ -1,15 +1,15 @@
-use actix_web::{App, HttpServer, Scope};
+use actix_web::{dev::Service, http::header::{HeaderName, HeaderValue, STRICT_TRANSPORT_SECURITY}, middleware::{from_fn, Condition, DefaultHeaders}, web::to, App, HttpServer, Scope};
+use futures_util::future::try_join;
 use flexi_logger::{Logger, FlexiLoggerError};
-use helpers::misc::logging::format_colored_log;
-use routes::{auth::{get_user, login, signup}, keys::{get_key_ids, handle_success_payment, pay_new_key, reset_key}, values::{random_bool, random_color, random_signed, random_unsigned}};
+use helpers::{authentication::signing::{init_signing_key, SigningError}, generator::{archive::{archive_settings, run_archive_cleanup}, beacon::{beacon_period, run_beacon}, drand::{drand_chain, run_drand}}, http::{context::{with_context, RequestContext}, errors::configure_extractors, tls::{hsts_header, redirect_server, reload_on_hangup, CertificateResolver, TlsError}, versioning::deprecated_alias}, misc::{config::init_config, logging::format_colored_log}};
+use log::warn;
+use models::{draw::run_draws, receipt::{retention_days, run_receipt_purge, Receipt}};
+use routes::{docs, frontend::{serve_frontend, API_PREFIX}, signatures::public_key, v1};
 use tokio::main;
 use thiserror::Error;
-use std::io::Error as IoError;
-
-#[macro_use]
-extern crate litcrypt;
-
-use_litcrypt!();
+use std::{io::Error as IoError, process::exit};
+#[cfg(feature = "grpc")]
+use routes::grpc::run_grpc;
 
 mod helpers;
 mod routes;
@@ -22,41 +22,129 @@ enum AppError {
 
     #[error("{0:#}")]
     Logger(#[from] FlexiLoggerError),
+
+    #[error("{0:#}")]
+    Signing(#[from] SigningError),
+
+    #[error("{0:#}")]
+    Tls(#[from] TlsError),
 }
 
 #[main]
 async fn main() -> Result<(), AppError> {
-    Logger::try_with_str("debug")?
+    let config = match init_config() {
+        Ok(config) => config,
+        Err(errors) => {
+            eprint!("{errors}");
+            exit(1);
+        }
+    };
+
+    Logger::try_with_str(&config.logging.level)?
         .format_for_stdout(format_colored_log)
         .log_to_stdout()
         .start()?;
 
-    HttpServer::new(move || {
+    init_signing_key()?;
+
+    if let Some(period) = beacon_period() {
+        tokio::spawn(run_beacon(period));
+    }
+
+    if let Some(chain) = drand_chain() {
+        tokio::spawn(run_drand(chain));
+    }
+
+    if let Some(settings) = archive_settings() {
+        tokio::spawn(run_archive_cleanup(settings));
+    }
+
+    if let Some(days) = retention_days() {
+        tokio::spawn(run_receipt_purge(days));
+    }
+
+    tokio::spawn(run_draws());
+
+    #[cfg(feature = "grpc")]
+    tokio::spawn(run_grpc(config.grpc.bind));
+
+    // hsts is only sent over tls, browsers ignore it on plain http anyway.
+    let hsts = config.tls
+        .as_ref()
+        .map_or(0, |tls| tls.hsts_max_age);
+
+    let mut server = HttpServer::new(move || {
         App::new()
+            .wrap(Condition::new(
+                hsts > 0,
+                DefaultHeaders::new().add((STRICT_TRANSPORT_SECURITY, hsts_header(hsts)))
+            ))
+            .wrap_fn(|mut req, srv| {
+                let context = RequestContext::new(&mut req);
+                let response = with_context(context.clone(), || srv.call(req));
+
+                async move {
+                    let mut response = response.await;
+
+                    if let Err(error) = Receipt::store_pending(&context).await {
+                        warn!("Couldn't store a receipt, an error occurred: {error:#}");
+                    }
+
+                    if let (Ok(response), Ok(id)) = (&mut response, HeaderValue::from_str(&context.borrow().request_id)) {
+                        response
+                            .headers_mut()
+                            .insert(HeaderName::from_static("x-request-id"), id);
+                    }
+
+                    response
+                }
+            })
+            .configure(configure_extractors)
+            // everything outside of /api is the frontend bundle, when one is served.
+            .default_service(to(serve_frontend))
             .service(
-                Scope::new("/random")
-                    .service(random_unsigned)
-                    .service(random_signed)
-                    .service(random_bool)
-                    .service(random_color)
-            )
-            .service(
-                Scope::new("/auth")
-                    .service(login)
-                    .service(signup)
-                    .service(get_user)
-            )
-            .service(
-                Scope::new("/keys")
-                    .service(get_key_ids)
-                    .service(reset_key)
-                    .service(pay_new_key)
-                    .service(handle_success_payment)
+                Scope::new(API_PREFIX)
+                    .service(public_key)
+                    .configure(docs::configure)
+                    .service(
+                        Scope::new("/v1")
+                            .configure(v1::configure)
+                    )
+                    // the unversioned paths are kept as deprecated aliases of the current version.
+                    .service(
+                        Scope::new("")
+                            .wrap(from_fn(deprecated_alias))
+                            .configure(v1::configure)
+                    )
             )
-    })
-        .bind(("127.0.0.1", 5174))?
-        .run()
-        .await?;
+    });
+
+    if let Some(workers) = config.server.workers {
+        server = server.workers(workers);
+    }
+
+    let Some(tls) = &config.tls else {
+        server
+            .bind(config.server.bind)?
+            .run()
+            .await?;
+
+        return Ok(());
+    };
+
+    let resolver = CertificateResolver::load(tls)?;
+    tokio::spawn(reload_on_hangup(resolver.clone()));
+
+    let server = server
+        .bind_rustls(config.server.bind, resolver.server_config())?
+        .run();
+
+    match tls.redirect {
+        Some(address) => {
+            try_join(server, redirect_server(address, config.server.bind.port())?).await?;
+        },
+        None => server.await?
+    }
 
     Ok(())
 }
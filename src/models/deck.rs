@@ -0,0 +1,246 @@
+use actix_web::web::Bytes;
+use serde::{Deserialize, Serialize};
+use sqlx::{query, query_as, Error as SqlxError};
+use thiserror::Error;
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+use crate::{db, helpers::{database::connection::DbConnectionError, generator::random::shuffle}};
+
+const MAX_DECKS: usize = 8;
+const MAX_CUSTOM_CARDS: usize = 1000;
+const RANKS: [&str; 13] = ["A", "2", "3", "4", "5", "6", "7", "8", "9", "10", "J", "Q", "K"];
+const SUITS: [&str; 4] = ["S", "H", "D", "C"];
+
+#[derive(Error, Debug)]
+pub enum DeckError {
+    #[error("Query: {0:#}")]
+    Query(#[from] SqlxError),
+
+    #[error("Connection: {0:#}")]
+    Connection(#[from] DbConnectionError),
+
+    #[error("A deck must be built from 1 to {MAX_DECKS} decks.")]
+    DeckCount,
+
+    #[error("A custom deck needs from 1 to {MAX_CUSTOM_CARDS} cards.")]
+    CustomCards,
+
+    #[error("Only {0} cards remain in the deck.")]
+    NotEnoughCards(usize)
+}
+
+#[derive(Deserialize, Serialize, ToSchema, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DeckKind {
+    #[default]
+    Standard,
+    Jokers,
+    Custom
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct DeckOptions {
+    #[serde(default)]
+    kind: DeckKind,
+    decks: Option<usize>,
+    cards: Option<Vec<String>>
+}
+
+pub struct Deck {
+    pub id: i32,
+    pub key_id: i32,
+    pub cards: Vec<String>,
+    pub remaining: Vec<String>,
+    pub created_at: OffsetDateTime
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DeckInfo {
+    id: i32,
+    key_id: i32,
+    size: usize,
+    remaining: usize,
+    created_at: i64
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DrawnCards {
+    deck: DeckInfo,
+    cards: Vec<String>
+}
+
+impl DeckOptions {
+    pub fn cards(self) -> Result<Vec<String>, DeckError> {
+        let decks = self.decks.unwrap_or(1);
+
+        if !(1..=MAX_DECKS).contains(&decks) {
+            return Err(DeckError::DeckCount);
+        }
+
+        let mut deck = match self.kind {
+            DeckKind::Standard | DeckKind::Jokers => SUITS
+                .iter()
+                .flat_map(|suit| RANKS.map(|rank| format!("{rank}{suit}")))
+                .collect::<Vec<_>>(),
+            DeckKind::Custom => match self.cards {
+                Some(cards) if (1..=MAX_CUSTOM_CARDS).contains(&cards.len()) => cards,
+                _ => return Err(DeckError::CustomCards)
+            }
+        };
+
+        if let DeckKind::Jokers = self.kind {
+            deck.extend(["JR".to_string(), "JB".to_string()]);
+        }
+
+        Ok(
+            (0..decks)
+                .flat_map(|_| deck.clone())
+                .collect()
+        )
+    }
+}
+
+impl Deck {
+    pub async fn new(key_id: i32, frame: &Bytes, cards: Vec<String>) -> Result<Self, DeckError> {
+        let mut remaining = cards.clone();
+
+        shuffle(frame, &mut remaining);
+
+        let deck = query_as!(
+            Self,
+            r#"
+                INSERT INTO decks (key_id, cards, remaining)
+                VALUES ($1, $2, $3)
+                RETURNING *
+            "#,
+            key_id,
+            &cards,
+            &remaining
+        )
+            .fetch_one(db!())
+            .await?;
+
+        Ok(deck)
+    }
+
+    pub async fn from_id(id: i32, key_id: i32) -> Result<Option<Self>, DeckError> {
+        let deck = query_as!(
+            Self,
+            r#"
+                SELECT *
+                FROM decks
+                WHERE id = $1 AND key_id = $2
+            "#,
+            id,
+            key_id
+        )
+            .fetch_optional(db!())
+            .await?;
+
+        Ok(deck)
+    }
+
+    pub async fn draw(id: i32, key_id: i32, amount: usize) -> Result<Option<DrawnCards>, DeckError> {
+        let mut transaction = db!().begin().await?;
+
+        let deck = query_as!(
+            Self,
+            r#"
+                SELECT *
+                FROM decks
+                WHERE id = $1 AND key_id = $2
+                FOR UPDATE
+            "#,
+            id,
+            key_id
+        )
+            .fetch_optional(&mut *transaction)
+            .await?;
+
+        let Some(mut deck) = deck else {
+            return Ok(None);
+        };
+
+        if amount > deck.remaining.len() {
+            return Err(DeckError::NotEnoughCards(deck.remaining.len()));
+        }
+
+        let cards = deck
+            .remaining
+            .drain(..amount)
+            .collect::<Vec<_>>();
+
+        query!(
+            r#"
+                UPDATE decks
+                SET remaining = $2
+                WHERE id = $1
+            "#,
+            id,
+            &deck.remaining
+        )
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(Some(DrawnCards {
+            deck: deck.info(),
+            cards
+        }))
+    }
+
+    pub async fn reshuffle(id: i32, key_id: i32, frame: &Bytes, all: bool) -> Result<Option<Self>, DeckError> {
+        let mut transaction = db!().begin().await?;
+
+        let deck = query_as!(
+            Self,
+            r#"
+                SELECT *
+                FROM decks
+                WHERE id = $1 AND key_id = $2
+                FOR UPDATE
+            "#,
+            id,
+            key_id
+        )
+            .fetch_optional(&mut *transaction)
+            .await?;
+
+        let Some(mut deck) = deck else {
+            return Ok(None);
+        };
+
+        if all {
+            deck.remaining = deck.cards.clone();
+        }
+
+        shuffle(frame, &mut deck.remaining);
+
+        query!(
+            r#"
+                UPDATE decks
+                SET remaining = $2
+                WHERE id = $1
+            "#,
+            id,
+            &deck.remaining
+        )
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        Ok(Some(deck))
+    }
+
+    pub fn info(&self) -> DeckInfo {
+        DeckInfo {
+            id: self.id,
+            key_id: self.key_id,
+            size: self.cards.len(),
+            remaining: self.remaining.len(),
+            created_at: self.created_at.unix_timestamp()
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use std::{collections::HashSet, time::Duration};
+use actix_web::web::Bytes;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{to_string, Error as JsonError};
+use sqlx::{query_as, query_scalar, Error as SqlxError};
+use thiserror::Error;
+use time::OffsetDateTime;
+use tokio::{select, sync::Notify, time::sleep};
+use utoipa::ToSchema;
+use crate::{db, helpers::{database::connection::DbConnectionError, generator::{frame::{frame_bytes_from_source, CaptureError}, random::get_unsigned}, misc::digest::{sha256, sha256_hex}}};
+
+const MAX_PARTICIPANTS: usize = 10000;
+
+// new draws wake the scheduler, the wait is still capped so draws created
+// by another instance are performed on time as well.
+const MAX_SCHEDULER_WAIT: Duration = Duration::from_secs(30);
+const SCHEDULER_RETRY: Duration = Duration::from_secs(5);
+
+static SCHEDULED: Notify = Notify::const_new();
+
+#[derive(Error, Debug)]
+pub enum DrawError {
+    #[error("Query: {0:#}")]
+    Query(#[from] SqlxError),
+
+    #[error("Connection: {0:#}")]
+    Connection(#[from] DbConnectionError),
+
+    #[error("Json: {0:#}")]
+    Json(#[from] JsonError),
+
+    #[error("{0:#}")]
+    Capture(#[from] CaptureError<'static>),
+
+    #[error("A draw needs from 1 to {MAX_PARTICIPANTS} unique participants.")]
+    Participants,
+
+    #[error("The amount of winners must be between 1 and the amount of participants.")]
+    Winners,
+
+    #[error("The draw time must be in the future.")]
+    DrawTime
+}
+
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct DrawOptions {
+    participants: Vec<String>,
+    winners: usize,
+    #[serde(with = "time::serde::rfc3339")]
+    draw_at: OffsetDateTime
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Draw {
+    pub id: i32,
+    pub key_id: i32,
+    pub participants: Vec<String>,
+    pub winners_count: i32,
+    pub commitment: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub draw_at: OffsetDateTime,
+    pub seed: Option<String>,
+    pub segment_digests: Option<Vec<String>>,
+    pub winners: Option<Vec<String>>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub drawn_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime
+}
+
+// anyone can recompute the winners from the published values:
+// - the commitment is the sha256 of the participants serialized as a json array.
+// - the draw key is the sha256 of the seed bytes followed by the commitment hex string.
+// - winners come from a partial Fisher-Yates shuffle where the swap for position `i` is
+//   `i + v % (n - i)`, `v` being the first 8 bytes (big endian) of sha256(key || counter),
+//   the counter is a big endian u64 that increases on every attempt and values above the
+//   last multiple of `n - i` are discarded.
+pub fn pick_winners(seed: &[u8], commitment: &str, participants: &[String], winners: usize) -> Vec<String> {
+    let key = sha256(&[seed, commitment.as_bytes()].concat());
+    let mut participants = participants.to_vec();
+    let mut counter = 0u64;
+
+    for index in 0..winners {
+        let bound = (participants.len() - index) as u64;
+        let zone = u64::MAX - (u64::MAX - bound + 1) % bound;
+
+        let value = loop {
+            let hash = sha256(&[&key[..], &counter.to_be_bytes()].concat());
+            let value = u64::from_be_bytes(hash[..8].try_into().unwrap_or_default());
+
+            counter += 1;
+
+            if value <= zone {
+                break value;
+            }
+        };
+
+        participants.swap(index, index + (value % bound) as usize);
+    }
+
+    participants.truncate(winners);
+    participants
+}
+
+impl Draw {
+    pub async fn new(key_id: i32, DrawOptions { participants, winners, draw_at }: DrawOptions)
+        -> Result<Self, DrawError> {
+
+        if
+            !(1..=MAX_PARTICIPANTS).contains(&participants.len())
+            || participants.iter().collect::<HashSet<_>>().len() != participants.len() {
+
+            return Err(DrawError::Participants);
+        }
+
+        if !(1..=participants.len()).contains(&winners) {
+            return Err(DrawError::Winners);
+        }
+
+        if draw_at <= OffsetDateTime::now_utc() {
+            return Err(DrawError::DrawTime);
+        }
+
+        let draw = query_as!(
+            Self,
+            r#"
+                INSERT INTO draws (key_id, participants, winners_count, commitment, draw_at)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING *
+            "#,
+            key_id,
+            &participants,
+            winners as i32,
+            sha256_hex(to_string(&participants)?.as_bytes()),
+            draw_at
+        )
+            .fetch_one(db!())
+            .await?;
+
+        SCHEDULED.notify_one();
+
+        Ok(draw)
+    }
+
+    pub async fn from_id(id: i32) -> Result<Option<Self>, DrawError> {
+        let draw = query_as!(
+            Self,
+            r#"
+                SELECT *
+                FROM draws
+                WHERE id = $1
+            "#,
+            id
+        )
+            .fetch_optional(db!())
+            .await?;
+
+        Ok(draw)
+    }
+
+    async fn due() -> Result<Vec<Self>, DrawError> {
+        let draws = query_as!(
+            Self,
+            r#"
+                SELECT *
+                FROM draws
+                WHERE winners IS NULL AND draw_at <= NOW()
+                ORDER BY draw_at
+            "#
+        )
+            .fetch_all(db!())
+            .await?;
+
+        Ok(draws)
+    }
+
+    async fn next_draw_at() -> Result<Option<OffsetDateTime>, DrawError> {
+        let next = query_scalar!(
+            r#"
+                SELECT MIN(draw_at)
+                FROM draws
+                WHERE winners IS NULL
+            "#
+        )
+            .fetch_one(db!())
+            .await?;
+
+        Ok(next)
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.winners.is_none() && self.draw_at <= OffsetDateTime::now_utc()
+    }
+
+    pub async fn perform(self, frame: &Bytes) -> Result<Self, DrawError> {
+        let seed = (0..8)
+            .flat_map(|_| get_unsigned(frame).to_be_bytes())
+            .collect::<Vec<_>>();
+
+        let winners = pick_winners(
+            &seed,
+            &self.commitment,
+            &self.participants,
+            self.winners_count as usize
+        );
+
+        // the draw is only stored if nobody else resolved it in the meantime.
+        let draw = query_as!(
+            Self,
+            r#"
+                UPDATE draws
+                SET seed = $2, segment_digests = $3, winners = $4, drawn_at = NOW()
+                WHERE id = $1 AND winners IS NULL
+                RETURNING *
+            "#,
+            self.id,
+            hex::encode(&seed),
+            &[sha256_hex(frame)],
+            &winners
+        )
+            .fetch_optional(db!())
+            .await?;
+
+        match draw {
+            Some(draw) => Ok(draw),
+            None => Ok(Self::from_id(self.id).await?.unwrap_or(self))
+        }
+    }
+}
+
+// performs the draws that are due, each with its own frame, and returns
+// the time of the next pending one.
+async fn perform_due_draws() -> Result<Option<OffsetDateTime>, DrawError> {
+    for draw in Draw::due().await? {
+        let frame = frame_bytes_from_source().await?;
+        let draw = draw.perform(&frame).await?;
+
+        info!("Performed draw {}.", draw.id);
+    }
+
+    Draw::next_draw_at().await
+}
+
+// draws are performed at their draw time, looking one up still performs it
+// if this task is late or not running.
+pub async fn run_draws() {
+    loop {
+        let wait = match perform_due_draws().await {
+            Ok(Some(next)) => (next - OffsetDateTime::now_utc())
+                .try_into()
+                .unwrap_or(Duration::ZERO)
+                .min(MAX_SCHEDULER_WAIT),
+            Ok(None) => MAX_SCHEDULER_WAIT,
+            Err(error) => {
+                warn!("Couldn't perform the due draws, an error occurred: {error:#}");
+                SCHEDULER_RETRY
+            }
+        };
+
+        select! {
+            _ = sleep(wait) => {},
+            _ = SCHEDULED.notified() => {}
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use std::{collections::HashMap, future::{ready, Future}, pin::Pin, sync::Mutex};
-use actix_web::{dev::Payload, error::{ErrorBadRequest, ErrorInternalServerError, ErrorTooManyRequests, ErrorUnauthorized}, http::header::ToStrError, Error as ActixWebError, FromRequest, HttpRequest};
+use actix_web::{dev::Payload, http::{header::ToStrError, StatusCode}, Error as ActixWebError, FromRequest, HttpRequest};
 use bcrypt::{hash, verify, BcryptError, DEFAULT_COST};
 use lazy_static::lazy_static;
 use log::debug;
@@ -7,8 +7,8 @@ use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use serde::Serialize;
 use serde_json::Error as JsonError;
 use sqlx::{query, query_as, Error as SqlxError};
-use time::{ext::NumericalDuration, OffsetDateTime};
-use crate::{db, helpers::database::connection::DbConnectionError};
+use time::{ext::NumericalDuration, Date, OffsetDateTime};
+use crate::{db, helpers::{database::connection::DbConnectionError, http::errors::ApiError, misc::config::config}};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -29,11 +29,41 @@ pub enum ApiKeyError {
     ToString(ToStrError)
 }
 
+#[derive(Error, Debug)]
+pub enum QuotaError {
+    #[error("The API key has exceeded its daily request allowance.")]
+    Requests,
+
+    #[error("The API key has exceeded its daily bit allowance.")]
+    Bits
+}
+
 pub enum MaybeApiKey {
     Authorized(ApiKey),
     Unauthorized
 }
 
+#[macro_export]
+macro_rules! require_key {
+    ($key:expr, $err:literal) => {
+        match $key {
+            $crate::models::key::MaybeApiKey::Authorized(key) => key,
+            $crate::models::key::MaybeApiKey::Unauthorized => {
+                return actix_web::HttpResponse::from(
+                    $crate::helpers::http::errors::ApiError::unauthorized("api_key_required", $err)
+                );
+            }
+        }
+    };
+}
+
+#[derive(Clone, Copy)]
+pub enum KeyTier {
+    Anonymous,
+    Supporter,
+    Patron
+}
+
 #[derive(Serialize)]
 pub struct ApiKey {
     pub id: i32,
@@ -44,6 +74,22 @@ pub struct ApiKey {
     pub created_at: OffsetDateTime
 }
 
+// usage is kept in memory and resets every day at midnight UTC.
+struct DailyUsage {
+    day: Date,
+    requests: u64,
+    bits: u64,
+    total_requests: u64,
+    total_bits: u64
+}
+
+pub struct KeyUsage {
+    pub requests_left: u64,
+    pub bits_left: u64,
+    pub total_requests: u64,
+    pub total_bits: u64
+}
+
 impl ApiKey {
     fn generate() -> String {
         thread_rng()
@@ -141,15 +187,147 @@ impl ApiKey {
             .fetch_one(db!())
             .await?
             .exists
-            .map_or(Ok(false), |v| Ok(v))
+            .map_or(Ok(false), Ok)
+    }
+}
+
+impl ApiKey {
+    pub fn tier(&self) -> KeyTier {
+        if self.paid >= 20.0 {
+            KeyTier::Patron
+        } else {
+            KeyTier::Supporter
+        }
+    }
+
+    fn with_usage<T>(&self, action: impl FnOnce(&mut DailyUsage) -> T) -> T {
+        let today = OffsetDateTime::now_utc().date();
+        let mut usage_dict = USAGE_DICT
+            .lock()
+            .unwrap();
+
+        let usage = usage_dict
+            .entry(self.id)
+            .or_insert(DailyUsage {
+                day: today,
+                requests: 0,
+                bits: 0,
+                total_requests: 0,
+                total_bits: 0
+            });
+
+        if usage.day != today {
+            usage.day = today;
+            usage.requests = 0;
+            usage.bits = 0;
+        }
+
+        action(usage)
+    }
+
+    fn key_usage(&self, usage: &DailyUsage) -> KeyUsage {
+        let tier = self.tier();
+
+        KeyUsage {
+            requests_left: tier.daily_requests().saturating_sub(usage.requests),
+            bits_left: tier.daily_bits().saturating_sub(usage.bits),
+            total_requests: usage.total_requests,
+            total_bits: usage.total_bits
+        }
+    }
+
+    pub fn usage(&self) -> KeyUsage {
+        self.with_usage(|usage| self.key_usage(usage))
+    }
+
+    fn charge(&self, requests: u64, bits: u64) -> Result<KeyUsage, QuotaError> {
+        self.with_usage(|usage| {
+            let left = self.key_usage(usage);
+
+            if left.requests_left < requests {
+                return Err(QuotaError::Requests);
+            }
+
+            if left.bits_left < bits {
+                return Err(QuotaError::Bits);
+            }
+
+            usage.requests += requests;
+            usage.bits += bits;
+            usage.total_requests += requests;
+            usage.total_bits += bits;
+
+            Ok(self.key_usage(usage))
+        })
+    }
+
+    pub fn consume(&self, bits: u64) -> Result<KeyUsage, QuotaError> {
+        self.charge(1, bits)
+    }
+
+    // streams count as a single request, each value only takes bits.
+    pub fn consume_bits(&self, bits: u64) -> Result<KeyUsage, QuotaError> {
+        self.charge(0, bits)
     }
 }
 
-impl Into<Option<ApiKey>> for MaybeApiKey {
-    fn into(self) -> Option<ApiKey> {
+impl KeyTier {
+    pub fn daily_requests(&self) -> u64 {
         match self {
-            Self::Authorized(key) => Some(key),
-            Self::Unauthorized => None
+            Self::Anonymous => 1_000,
+            Self::Supporter => 10_000,
+            Self::Patron => 100_000
+        }
+    }
+
+    pub fn daily_bits(&self) -> u64 {
+        match self {
+            Self::Anonymous => 250_000,
+            Self::Supporter => 5_000_000,
+            Self::Patron => 50_000_000
+        }
+    }
+
+    // values per second across all the streams of a key.
+    pub fn max_stream_rate(&self) -> f64 {
+        match self {
+            Self::Anonymous => 1.0,
+            Self::Supporter => 10.0,
+            Self::Patron => 100.0
+        }
+    }
+
+    pub fn max_bigint_bits(&self) -> u64 {
+        match self {
+            Self::Anonymous => 4096,
+            Self::Supporter => 65536,
+            Self::Patron => 262144
+        }
+    }
+
+    pub fn max_prime_bits(&self) -> u64 {
+        match self {
+            Self::Anonymous => 512,
+            Self::Supporter => 2048,
+            Self::Patron => 4096
+        }
+    }
+}
+
+impl MaybeApiKey {
+    pub fn tier(&self) -> KeyTier {
+        match self {
+            Self::Authorized(key) => key.tier(),
+            Self::Unauthorized => KeyTier::Anonymous
+        }
+    }
+}
+
+impl From<MaybeApiKey> for Option<ApiKey> {
+    fn from(value: MaybeApiKey) -> Self {
+        match value {
+            MaybeApiKey::Authorized(key) => Some(key),
+            MaybeApiKey::Unauthorized => None
         }
     }
 }
@@ -157,6 +335,9 @@ impl Into<Option<ApiKey>> for MaybeApiKey {
 lazy_static! {
     static ref RATE_LIMIT_DICT: Mutex<HashMap<String, OffsetDateTime>>
         = Mutex::new(HashMap::new());
+
+    static ref USAGE_DICT: Mutex<HashMap<i32, DailyUsage>>
+        = Mutex::new(HashMap::new());
 }
 
 impl FromRequest for MaybeApiKey {
@@ -168,9 +349,9 @@ impl FromRequest for MaybeApiKey {
         if let Some(value) = req.headers().get("Authorization") {
             let key = match value.to_str() {
                 Ok(key) => key.to_string(),
-                Err(err) => {
+                Err(_) => {
                     return Box::pin(ready(
-                        Err(ErrorInternalServerError(format!("{err:#}")))
+                        Err(ApiError::bad_request("invalid_api_key", "The provided key is not valid.").into())
                     ));
                 }
             };
@@ -178,8 +359,8 @@ impl FromRequest for MaybeApiKey {
             return Box::pin(async {
                 match ApiKey::from_key(key).await {
                     Ok(Some(key)) => Ok(Self::Authorized(key)),
-                    Ok(None) => Err(ErrorUnauthorized("The provided key is not valid")),
-                    Err(err) => Err(ErrorInternalServerError(format!("{err:#}")))
+                    Ok(None) => Err(ApiError::unauthorized("invalid_api_key", "The provided key is not valid.").into()),
+                    Err(err) => Err(ApiError::internal(err).into())
                 }
             });
         };
@@ -189,7 +370,7 @@ impl FromRequest for MaybeApiKey {
             None => {
                 return Box::pin(
                     ready(Err(
-                        ErrorBadRequest("Could not retrieve peer address.")
+                        ApiError::bad_request("unknown_peer", "Could not retrieve peer address.").into()
                     ))
                 );
             }
@@ -208,19 +389,21 @@ impl FromRequest for MaybeApiKey {
 
                 return Box::pin(
                     ready(Err(
-                        ErrorTooManyRequests(format!(
-                            "
-                            \rToo many requests, you will be able to make
-                            \ra request again in {} seconds, unless you provide an api key.
-                            ",
-                            (*time - now).whole_seconds()
-                        ))
+                        ApiError::new(
+                            StatusCode::TOO_MANY_REQUESTS,
+                            "rate_limited",
+                            format!(
+                                "Too many requests, you will be able to make a request again in {} seconds, unless you provide an api key.",
+                                (*time - now).whole_seconds()
+                            )
+                        )
+                            .into()
                     ))
                 );
             }
         }
 
-        let ratelimit = now + 30i64.seconds();
+        let ratelimit = now + (config().rate_limit.anonymous_interval as i64).seconds();
 
         debug!("Updating ratelimit for IP {ip} setting expiration to {ratelimit}");
 
@@ -1,3 +1,8 @@
 
 pub mod user;
 pub mod key;
+pub mod deck;
+pub mod draw;
+pub mod pulse;
+pub mod receipt;
+pub mod round;
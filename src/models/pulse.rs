@@ -0,0 +1,140 @@
+use serde::Serialize;
+use sqlx::{query, query_as, Error as SqlxError};
+use thiserror::Error;
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+use crate::{db, helpers::{database::connection::DbConnectionError, misc::digest::sha512_hex}};
+
+pub const BEACON_VERSION: &str = "2.0";
+pub const CHAIN_INDEX: i32 = 1;
+pub const CIPHER_SUITE: i32 = 0;
+pub const GENESIS_OUTPUT_VALUE: &str = "00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Error, Debug)]
+pub enum PulseError {
+    #[error("Query: {0:#}")]
+    Query(#[from] SqlxError),
+
+    #[error("Connection: {0:#}")]
+    Connection(#[from] DbConnectionError)
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Pulse {
+    pub pulse_index: i64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub time_stamp: OffsetDateTime,
+    pub period: i32,
+    pub local_random_value: String,
+    pub previous_output_value: String,
+    pub segment_digests: Vec<String>,
+    pub output_value: String
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PulseDocument<'p> {
+    version: &'static str,
+    cipher_suite: i32,
+    chain_index: i32,
+    #[serde(flatten)]
+    pulse: &'p Pulse
+}
+
+// the output value is the sha512 of the pulse index, the rfc 3339 timestamp,
+// the local random value, the previous output value and every segment digest,
+// concatenated in that order as utf-8 strings.
+pub fn output_value(index: i64, time_stamp: &str, local: &str, previous: &str, digests: &[String]) -> String {
+    sha512_hex(
+        [index.to_string().as_str(), time_stamp, local, previous]
+            .into_iter()
+            .chain(digests.iter().map(String::as_str))
+            .collect::<String>()
+            .as_bytes()
+    )
+}
+
+impl Pulse {
+    pub async fn latest() -> Result<Option<Self>, PulseError> {
+        let pulse = query_as!(
+            Self,
+            r#"
+                SELECT *
+                FROM beacon_pulses
+                ORDER BY pulse_index DESC
+                LIMIT 1
+            "#
+        )
+            .fetch_optional(db!())
+            .await?;
+
+        Ok(pulse)
+    }
+
+    pub async fn from_index(index: i64) -> Result<Option<Self>, PulseError> {
+        let pulse = query_as!(
+            Self,
+            r#"
+                SELECT *
+                FROM beacon_pulses
+                WHERE pulse_index = $1
+            "#,
+            index
+        )
+            .fetch_optional(db!())
+            .await?;
+
+        Ok(pulse)
+    }
+
+    pub async fn from_time(time: OffsetDateTime) -> Result<Option<Self>, PulseError> {
+        let pulse = query_as!(
+            Self,
+            r#"
+                SELECT *
+                FROM beacon_pulses
+                WHERE time_stamp >= $1
+                ORDER BY time_stamp ASC
+                LIMIT 1
+            "#,
+            time
+        )
+            .fetch_optional(db!())
+            .await?;
+
+        Ok(pulse)
+    }
+
+    pub async fn save(&self) -> Result<(), PulseError> {
+        query!(
+            r#"
+                INSERT INTO beacon_pulses (
+                    pulse_index, time_stamp, period, local_random_value,
+                    previous_output_value, segment_digests, output_value
+                )
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+            self.pulse_index,
+            self.time_stamp,
+            self.period,
+            self.local_random_value,
+            self.previous_output_value,
+            &self.segment_digests,
+            self.output_value
+        )
+            .execute(db!())
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn document(&self) -> PulseDocument {
+        PulseDocument {
+            version: BEACON_VERSION,
+            cipher_suite: CIPHER_SUITE,
+            chain_index: CHAIN_INDEX,
+            pulse: self
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+use log::{info, warn};
+use rand::{thread_rng, RngCore};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{query, query_as, Error as SqlxError};
+use thiserror::Error;
+use time::{error::ComponentRange, OffsetDateTime};
+use tokio::time::interval;
+use utoipa::ToSchema;
+use crate::{db, helpers::{database::connection::DbConnectionError, http::context::{PendingReceipt, RequestContext}, misc::config::config}};
+
+
+#[derive(Error, Debug)]
+pub enum ReceiptError {
+    #[error("Query: {0:#}")]
+    Query(#[from] SqlxError),
+
+    #[error("Connection: {0:#}")]
+    Connection(#[from] DbConnectionError),
+
+    #[error("Timestamp: {0:#}")]
+    Timestamp(#[from] ComponentRange)
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Receipt {
+    pub id: String,
+    pub key_id: Option<i32>,
+    pub endpoint: String,
+    pub parameters: String,
+    pub body: Option<Value>,
+    pub value: Value,
+    pub segment_digests: Vec<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub issued_at: OffsetDateTime
+}
+
+pub fn receipts_enabled(key_id: Option<i32>) -> bool {
+    key_id.is_some() || config().receipts.anonymous
+}
+
+pub fn generate_receipt_id() -> String {
+    let mut id = [0u8; 16];
+
+    thread_rng().fill_bytes(&mut id);
+
+    hex::encode(id)
+}
+
+// a retention of 0 days keeps receipts forever.
+pub fn retention_days() -> Option<i32> {
+    let days = config().receipts.retention_days;
+
+    (days > 0).then_some(days)
+}
+
+impl Receipt {
+    pub async fn store_pending(context: &Rc<RefCell<RequestContext>>) -> Result<(), ReceiptError> {
+        let (receipt, endpoint, parameters, body, segment_digests) = {
+            let mut context = context.borrow_mut();
+
+            let Some(receipt) = context.receipt.take() else {
+                return Ok(());
+            };
+
+            (
+                receipt,
+                context.endpoint.clone(),
+                context.parameters.clone(),
+                context.body.take(),
+                context.segment_digests.clone()
+            )
+        };
+
+        let PendingReceipt { id, key_id, value, timestamp } = receipt;
+
+        query!(
+            r#"
+                INSERT INTO receipts (id, key_id, endpoint, parameters, body, value, segment_digests, issued_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+            id,
+            key_id,
+            endpoint,
+            parameters,
+            body,
+            value,
+            &segment_digests,
+            OffsetDateTime::from_unix_timestamp(timestamp)?
+        )
+            .execute(db!())
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn from_id(id: &str) -> Result<Option<Self>, ReceiptError> {
+        let receipt = query_as!(
+            Self,
+            r#"
+                SELECT *
+                FROM receipts
+                WHERE id = $1
+            "#,
+            id
+        )
+            .fetch_optional(db!())
+            .await?;
+
+        Ok(receipt)
+    }
+
+    pub async fn purge(days: i32) -> Result<u64, ReceiptError> {
+        let deleted = query!(
+            r#"
+                DELETE FROM receipts
+                WHERE issued_at < NOW() - make_interval(days => $1)
+            "#,
+            days
+        )
+            .execute(db!())
+            .await?
+            .rows_affected();
+
+        Ok(deleted)
+    }
+}
+
+pub async fn run_receipt_purge(days: i32) {
+    let mut ticker = interval(Duration::from_secs(3600));
+
+    loop {
+        ticker.tick().await;
+
+        match Receipt::purge(days).await {
+            Ok(0) => {},
+            Ok(deleted) => info!("Purged {deleted} receipts older than {days} days."),
+            Err(error) => warn!("Couldn't purge old receipts, an error occurred: {error:#}")
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use hex::FromHexError;
+use serde::Serialize;
+use sqlx::{query, query_as, Error as SqlxError};
+use thiserror::Error;
+use time::OffsetDateTime;
+use utoipa::ToSchema;
+use crate::{db, helpers::{database::connection::DbConnectionError, misc::digest::sha256_hex}};
+
+#[derive(Error, Debug)]
+pub enum RoundError {
+    #[error("Query: {0:#}")]
+    Query(#[from] SqlxError),
+
+    #[error("Connection: {0:#}")]
+    Connection(#[from] DbConnectionError),
+
+    #[error("Hex: {0:#}")]
+    Hex(#[from] FromHexError)
+}
+
+pub struct Round {
+    pub round: i64,
+    pub signature: String,
+    pub previous_signature: String,
+    pub local_random_value: String,
+    pub segment_digests: Vec<String>,
+    pub created_at: OffsetDateTime
+}
+
+// the same shape drand serves for its rounds, the local value and
+// digests are extra fields needed to verify our signatures.
+#[derive(Serialize, ToSchema)]
+pub struct RoundDocument<'r> {
+    round: i64,
+    randomness: String,
+    signature: &'r str,
+    previous_signature: &'r str,
+    local_random_value: &'r str,
+    segment_digests: &'r [String]
+}
+
+impl Round {
+    pub async fn latest() -> Result<Option<Self>, RoundError> {
+        let round = query_as!(
+            Self,
+            r#"
+                SELECT *
+                FROM drand_rounds
+                ORDER BY round DESC
+                LIMIT 1
+            "#
+        )
+            .fetch_optional(db!())
+            .await?;
+
+        Ok(round)
+    }
+
+    pub async fn from_round(round: i64) -> Result<Option<Self>, RoundError> {
+        let round = query_as!(
+            Self,
+            r#"
+                SELECT *
+                FROM drand_rounds
+                WHERE round = $1
+            "#,
+            round
+        )
+            .fetch_optional(db!())
+            .await?;
+
+        Ok(round)
+    }
+
+    pub async fn save(&self) -> Result<(), RoundError> {
+        query!(
+            r#"
+                INSERT INTO drand_rounds (
+                    round, signature, previous_signature,
+                    local_random_value, segment_digests, created_at
+                )
+                VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            self.round,
+            self.signature,
+            self.previous_signature,
+            self.local_random_value,
+            &self.segment_digests,
+            self.created_at
+        )
+            .execute(db!())
+            .await?;
+
+        Ok(())
+    }
+
+    pub fn document(&self) -> Result<RoundDocument, RoundError> {
+        Ok(RoundDocument {
+            round: self.round,
+            randomness: sha256_hex(&hex::decode(&self.signature)?),
+            signature: &self.signature,
+            previous_signature: &self.previous_signature,
+            local_random_value: &self.local_random_value,
+            segment_digests: &self.segment_digests
+        })
+    }
+}
@@ -1,5 +1,5 @@
 use std::{future::{ready, Future}, pin::Pin, time::Duration};
-use actix_web::{dev::Payload, error::ErrorUnauthorized, Error as ActixWebError, FromRequest, HttpRequest};
+use actix_web::{dev::Payload, Error as ActixWebError, FromRequest, HttpRequest};
 use bcrypt::{hash, verify, BcryptError, DEFAULT_COST};
 use serde::{Deserialize, Serialize};
 use serde_json::{to_string, Error as JsonError};
@@ -7,7 +7,8 @@ use sqlx::{query, query_as, Error as SqlxError};
 use thiserror::Error;
 use time::OffsetDateTime;
 use jsonwebtoken::{decode, encode, errors::Error as JwtError, Header, Validation};
-use crate::{db, helpers::database::connection::DbConnectionError, jwt_hash};
+use utoipa::ToSchema;
+use crate::{db, helpers::{database::connection::DbConnectionError, http::errors::ApiError}, jwt_hash};
 
 #[derive(Error, Debug)]
 pub enum UserError {
@@ -40,7 +41,7 @@ struct UserClaims {
     created_at: OffsetDateTime
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct User {
     pub id: i32,
     pub email: String,
@@ -175,7 +176,7 @@ impl FromRequest for User {
                         .ok()
                 )
                     .ok_or(
-                        ErrorUnauthorized("Missing or invalid auth cookie for this endpoint.")
+                        ActixWebError::from(ApiError::unauthorized("auth_required", "Missing or invalid auth cookie for this endpoint."))
                     )
         ))
     }
@@ -0,0 +1,31 @@
+use actix_web::{get, web::Path, HttpResponse, Responder};
+use utoipa::OpenApi;
+use crate::{grv, helpers::{generator::archive::archive_settings, http::errors::{ApiError, ErrorEnvelope}}};
+
+#[derive(OpenApi)]
+#[openapi(paths(get_segment))]
+pub struct ArchiveApi;
+
+#[utoipa::path(
+    summary = "Archived segment by its sha256 digest",
+    responses(
+        (status = 200, content_type = "video/mp2t", description = "The MPEG-TS segment"),
+        (status = 404, body = ErrorEnvelope)
+    )
+)]
+#[get("/{digest}")]
+pub async fn get_segment(digest: Path<String>) -> impl Responder {
+    let Some(settings) = archive_settings() else {
+        return ApiError::not_found("archive_disabled", "This instance doesn't archive segments.")
+            .into();
+    };
+
+    match grv!(settings.load(&digest).await) {
+        Some(bytes) => HttpResponse::Ok()
+            .content_type("video/mp2t")
+            .insert_header(("Cache-Control", "public, max-age=31536000, immutable"))
+            .body(bytes),
+        None => ApiError::not_found("segment_not_found", "The segment isn't archived.")
+            .into()
+    }
+}
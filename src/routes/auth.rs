@@ -2,9 +2,14 @@ use std::time::Duration;
 use actix_web::{cookie::Cookie, get, http::header::ContentType, post, web::Json, HttpResponse, Responder};
 use serde::Deserialize;
 use time::OffsetDateTime;
-use crate::{gov, grv, models::user::{User, UserError}};
+use utoipa::{OpenApi, ToSchema};
+use crate::{gov, grv, helpers::http::errors::{ApiError, ErrorEnvelope}, models::user::User};
 
-#[derive(Deserialize)]
+#[derive(OpenApi)]
+#[openapi(paths(login, signup, get_user))]
+pub struct AuthApi;
+
+#[derive(Deserialize, ToSchema)]
 struct LoginInfo {
     email: String,
     password: String
@@ -19,13 +24,20 @@ fn create_auth_cookie(jwt: &String) -> Cookie {
     cookie
 }
 
+#[utoipa::path(
+    summary = "Log in, sets the auth cookie",
+    responses(
+        (status = 200, description = "The user serialized as a JSON string", body = String),
+        (status = 401, body = ErrorEnvelope)
+    )
+)]
 #[post("/login")]
 pub async fn login(info: Json<LoginInfo>) -> impl Responder {
     let LoginInfo {email, password} = info.into_inner();
 
     let user = gov!(
         grv!(User::login(email, password).await),
-        "User not found, check the email or password and try again."
+        ApiError::unauthorized("invalid_credentials", "User not found, check the email or password and try again.")
     );
 
     HttpResponse::Ok()
@@ -33,22 +45,20 @@ pub async fn login(info: Json<LoginInfo>) -> impl Responder {
         .json(grv!(user.to_string()))
 }
 
+#[utoipa::path(
+    summary = "Register a new user, sets the auth cookie",
+    responses(
+        (status = 200, description = "The user serialized as a JSON string", body = String),
+        (status = 400, body = ErrorEnvelope)
+    )
+)]
 #[post("/register")]
 pub async fn signup(info: Json<LoginInfo>) -> impl Responder {
     let LoginInfo { email, password } = info.into_inner();
 
     let user = match User::new(email, password).await {
         Ok(user) => user,
-        Err(err) => match err {
-            UserError::EmailConflict(_) => {
-                return HttpResponse::BadRequest()
-                    .body(err.to_string());
-            },
-            _ => {
-                return HttpResponse::InternalServerError()
-                    .body(err.to_string());
-            }
-        }
+        Err(err) => return ApiError::from(err).into()
     };
 
     HttpResponse::Ok()
@@ -57,6 +67,14 @@ pub async fn signup(info: Json<LoginInfo>) -> impl Responder {
 }
 
 
+#[utoipa::path(
+    summary = "The logged in user",
+    responses(
+        (status = 200, body = User),
+        (status = 401, body = ErrorEnvelope)
+    ),
+    security(("auth_cookie" = []))
+)]
 #[get("/user")]
 pub async fn get_user(user: User) -> impl Responder {
     HttpResponse::Ok()
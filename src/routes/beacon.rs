@@ -0,0 +1,76 @@
+use actix_web::{get, web::Path, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use crate::{grv, helpers::http::{errors::{ApiError, ErrorEnvelope}, query::LiteralQuery}, models::pulse::{Pulse, PulseDocument}};
+
+#[derive(OpenApi)]
+#[openapi(paths(latest_pulse, pulse_at, pulse_by_index))]
+pub struct BeaconApi;
+
+#[derive(Serialize, ToSchema)]
+struct PulseResponse<'p> {
+    pulse: PulseDocument<'p>
+}
+
+fn pulse_response(pulse: Option<Pulse>) -> HttpResponse {
+    match pulse {
+        Some(pulse) => HttpResponse::Ok()
+            .json(PulseResponse { pulse: pulse.document() }),
+        None => ApiError::not_found("pulse_not_found", "The requested pulse doesn't exist.")
+            .into()
+    }
+}
+
+#[utoipa::path(
+    summary = "Latest beacon pulse",
+    responses(
+        (status = 200, body = PulseResponse),
+        (status = 404, body = ErrorEnvelope)
+    )
+)]
+#[get("/latest")]
+pub async fn latest_pulse() -> impl Responder {
+    pulse_response(grv!(Pulse::latest().await))
+}
+
+#[derive(Deserialize, IntoParams)]
+struct PulseTimeQuery {
+    time: String
+}
+
+#[utoipa::path(
+    summary = "First pulse emitted at or after a time, given as RFC 3339 or unix seconds",
+    params(PulseTimeQuery),
+    responses(
+        (status = 200, body = PulseResponse),
+        (status = 400, body = ErrorEnvelope),
+        (status = 404, body = ErrorEnvelope)
+    )
+)]
+#[get("/at")]
+pub async fn pulse_at(query: LiteralQuery<PulseTimeQuery>) -> impl Responder {
+    let time = match query.time.parse::<i64>() {
+        Ok(timestamp) => OffsetDateTime::from_unix_timestamp(timestamp).ok(),
+        Err(_) => OffsetDateTime::parse(&query.time, &Rfc3339).ok()
+    };
+
+    let Some(time) = time else {
+        return ApiError::bad_request("invalid_time", "Invalid time parameter, expected an RFC 3339 timestamp or unix seconds.")
+            .into();
+    };
+
+    pulse_response(grv!(Pulse::from_time(time).await))
+}
+
+#[utoipa::path(
+    summary = "Pulse by index",
+    responses(
+        (status = 200, body = PulseResponse),
+        (status = 404, body = ErrorEnvelope)
+    )
+)]
+#[get("/{index}")]
+pub async fn pulse_by_index(index: Path<i64>) -> impl Responder {
+    pulse_response(grv!(Pulse::from_index(*index).await))
+}
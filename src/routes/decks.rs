@@ -0,0 +1,115 @@
+use actix_web::{get, post, web::{Json, Path, Query}, HttpResponse, Responder};
+use serde::Deserialize;
+use utoipa::{IntoParams, OpenApi};
+use crate::{frame_bytes, grv, require_key, helpers::http::{context::record_body, errors::{ApiError, ErrorEnvelope}, responses::TimedResponse}, models::{deck::{Deck, DeckInfo, DeckOptions, DrawnCards}, key::MaybeApiKey}};
+
+#[derive(OpenApi)]
+#[openapi(paths(create_deck, get_deck, draw_cards, reshuffle_deck))]
+pub struct DecksApi;
+
+fn deck_not_found() -> HttpResponse {
+    ApiError::not_found("deck_not_found", "The deck doesn't exist or belongs to another key.")
+        .into()
+}
+
+#[utoipa::path(
+    summary = "Create a shuffled deck of cards",
+    responses(
+        (status = 200, body = TimedResponse<DeckInfo>),
+        (status = 400, body = ErrorEnvelope),
+        (status = 401, body = ErrorEnvelope)
+    ),
+    security(("api_key" = []))
+)]
+#[post("/decks")]
+pub async fn create_deck(key: MaybeApiKey, options: Json<DeckOptions>) -> impl Responder {
+    let key = require_key!(key, "An api key is required to manage decks.");
+
+    record_body(&*options);
+
+    let cards = match options.into_inner().cards() {
+        Ok(cards) => cards,
+        Err(error) => return ApiError::from(error).into()
+    };
+
+    let deck = grv!(Deck::new(key.id, &frame_bytes!(), cards).await);
+
+    TimedResponse::new(deck.info(), Some(key))
+        .into()
+}
+
+#[utoipa::path(
+    summary = "Deck by id",
+    responses(
+        (status = 200, body = TimedResponse<DeckInfo>),
+        (status = 404, body = ErrorEnvelope),
+        (status = 401, body = ErrorEnvelope)
+    ),
+    security(("api_key" = []))
+)]
+#[get("/decks/{id}")]
+pub async fn get_deck(key: MaybeApiKey, id: Path<i32>) -> impl Responder {
+    let key = require_key!(key, "An api key is required to manage decks.");
+
+    match grv!(Deck::from_id(*id, key.id).await) {
+        Some(deck) => TimedResponse::new(deck.info(), Some(key))
+            .into(),
+        None => deck_not_found()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+struct DrawQuery {
+    n: Option<usize>
+}
+
+#[utoipa::path(
+    summary = "Draw cards from the top of a deck",
+    params(DrawQuery),
+    responses(
+        (status = 200, body = TimedResponse<DrawnCards>),
+        (status = 400, body = ErrorEnvelope),
+        (status = 404, body = ErrorEnvelope),
+        (status = 401, body = ErrorEnvelope)
+    ),
+    security(("api_key" = []))
+)]
+#[post("/decks/{id}/draw")]
+pub async fn draw_cards(key: MaybeApiKey, id: Path<i32>, query: Query<DrawQuery>) -> impl Responder {
+    let key = require_key!(key, "An api key is required to manage decks.");
+
+    match Deck::draw(*id, key.id, query.n.unwrap_or(1)).await {
+        Ok(Some(drawn)) => TimedResponse::new(drawn, Some(key))
+            .into(),
+        Ok(None) => deck_not_found(),
+        Err(error) => ApiError::from(error)
+            .into()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+struct ShuffleQuery {
+    all: Option<bool>
+}
+
+#[utoipa::path(
+    summary = "Shuffle the remaining cards, or every card with all=true",
+    params(ShuffleQuery),
+    responses(
+        (status = 200, body = TimedResponse<DeckInfo>),
+        (status = 404, body = ErrorEnvelope),
+        (status = 401, body = ErrorEnvelope)
+    ),
+    security(("api_key" = []))
+)]
+#[post("/decks/{id}/shuffle")]
+pub async fn reshuffle_deck(key: MaybeApiKey, id: Path<i32>, query: Query<ShuffleQuery>) -> impl Responder {
+    let key = require_key!(key, "An api key is required to manage decks.");
+    let bytes = frame_bytes!();
+
+    match grv!(Deck::reshuffle(*id, key.id, &bytes, query.all.unwrap_or(false)).await) {
+        Some(deck) => TimedResponse::new(deck.info(), Some(key))
+            .into(),
+        None => deck_not_found()
+    }
+}
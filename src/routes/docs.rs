@@ -0,0 +1,139 @@
+use std::sync::OnceLock;
+use actix_web::{get, web::ServiceConfig, HttpResponse, Responder};
+use utoipa::{openapi::{schema::{ObjectBuilder, Type}, security::{ApiKey, ApiKeyValue, SecurityScheme}, ContentBuilder, OpenApi as OpenApiDocument, Ref, RefOr, ResponseBuilder}, Modify, OpenApi};
+use utoipa_scalar::{Scalar, Servable};
+use crate::helpers::{http::errors::ErrorEnvelope, misc::config::config};
+use super::v1::V1Api;
+
+struct SecuritySchemes;
+
+impl Modify for SecuritySchemes {
+    fn modify(&self, openapi: &mut OpenApiDocument) {
+        let components = openapi
+            .components
+            .get_or_insert_with(Default::default);
+
+        let anonymous_limit = format!(
+            "Requests without an API key are limited to 1 every {} seconds.",
+            config().rate_limit.anonymous_interval
+        );
+
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                "Authorization",
+                &anonymous_limit
+            )))
+        );
+
+        components.add_security_scheme(
+            "auth_cookie",
+            SecurityScheme::ApiKey(ApiKey::Cookie(ApiKeyValue::new("auth")))
+        );
+    }
+}
+
+// any operation can be rate limited or fail internally, those answer
+// with the error envelope so it's documented as the default response.
+struct DefaultErrors;
+
+impl Modify for DefaultErrors {
+    fn modify(&self, openapi: &mut OpenApiDocument) {
+        let response = ResponseBuilder::new()
+            .description("Error envelope")
+            .content(
+                "application/json",
+                ContentBuilder::new()
+                    .schema(Some(Ref::from_schema_name("ErrorEnvelope")))
+                    .build()
+            )
+            .build();
+
+        for item in openapi.paths.paths.values_mut() {
+            for operation in [&mut item.get, &mut item.post].into_iter().flatten() {
+                operation
+                    .responses
+                    .responses
+                    .entry("default".into())
+                    .or_insert_with(|| response.clone().into());
+            }
+        }
+    }
+}
+
+// timed responses can also be negotiated as text and csv, which only
+// carry the value, or as msgpack and cbor with the same shape as json.
+struct NegotiatedFormats;
+
+impl Modify for NegotiatedFormats {
+    fn modify(&self, openapi: &mut OpenApiDocument) {
+        let text = ContentBuilder::new()
+            .schema(Some(ObjectBuilder::new().schema_type(Type::String)))
+            .build();
+
+        for item in openapi.paths.paths.values_mut() {
+            for operation in [&mut item.get, &mut item.post].into_iter().flatten() {
+                let Some(RefOr::T(response)) = operation.responses.responses.get_mut("200") else {
+                    continue;
+                };
+
+                let Some(json) = response.content.get("application/json").cloned() else {
+                    continue;
+                };
+
+                if !matches!(&json.schema, Some(RefOr::Ref(schema)) if schema.ref_location.contains("/TimedResponse_")) {
+                    continue;
+                }
+
+                response.content.insert("text/plain".into(), text.clone());
+                response.content.insert("text/csv".into(), text.clone());
+                response.content.insert("application/msgpack".into(), json.clone());
+                response.content.insert("application/cbor".into(), json);
+            }
+        }
+    }
+}
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "RLARNDG",
+        description = "Random values generated from the footage of cameras around the world. Values can be requested \
+            as text/plain, text/csv, application/msgpack or application/cbor through the Accept header or \
+            `output=text|csv|msgpack|cbor|json`. The parameter is named `output` rather than `format` since \
+            several endpoints already take a `format` parameter for the value itself."
+    ),
+    servers((url = "/api")),
+    paths(super::signatures::public_key),
+    nest((path = "/v1", api = V1Api)),
+    components(schemas(ErrorEnvelope)),
+    modifiers(&SecuritySchemes, &DefaultErrors, &NegotiatedFormats)
+)]
+struct ApiDoc;
+
+static DOCUMENT: OnceLock<OpenApiDocument> = OnceLock::new();
+
+fn document() -> &'static OpenApiDocument {
+    DOCUMENT.get_or_init(|| {
+        let mut document = ApiDoc::openapi();
+
+        // the package doesn't declare a license, utoipa would emit an empty one.
+        document.info.license = None;
+
+        document
+    })
+}
+
+#[get("/openapi.json")]
+pub async fn openapi_json() -> impl Responder {
+    HttpResponse::Ok()
+        .json(document())
+}
+
+// both are served outside of the versioned scopes, the document
+// describes the /v1 paths.
+pub fn configure(config: &mut ServiceConfig) {
+    config
+        .service(openapi_json)
+        .service(Scalar::with_url("/docs", document().clone()));
+}
@@ -0,0 +1,89 @@
+use actix_web::{get, web::Path, HttpResponse, Responder};
+use time::OffsetDateTime;
+use utoipa::OpenApi;
+use crate::{grv, helpers::{generator::drand::{drand_chain, ChainInfo, DrandChain}, http::errors::{ApiError, ErrorEnvelope}}, models::round::{Round, RoundDocument}};
+
+#[derive(OpenApi)]
+#[openapi(paths(chain_info, latest_round, round_by_number))]
+pub struct DrandApi;
+
+fn disabled() -> HttpResponse {
+    ApiError::not_found("drand_disabled", "This instance doesn't serve a drand chain.")
+        .into()
+}
+
+fn round_response(round: Option<Round>, cache_control: String) -> HttpResponse {
+    let Some(round) = round else {
+        return ApiError::not_found("round_not_found", "The requested round isn't available.")
+            .into();
+    };
+
+    HttpResponse::Ok()
+        .insert_header(("Cache-Control", cache_control))
+        .json(grv!(round.document()))
+}
+
+// the latest round can be cached until the next one is due.
+fn latest_cache_control(chain: &DrandChain) -> String {
+    let now = OffsetDateTime::now_utc().unix_timestamp();
+    let next = chain.round_time(chain.round_at(now) + 1);
+
+    format!("public, max-age={}", (next - now).max(1))
+}
+
+#[utoipa::path(
+    summary = "Chain parameters in the shape drand serves them, rounds are signed with ed25519",
+    responses(
+        (status = 200, body = ChainInfo),
+        (status = 404, description = "The chain is disabled", body = ErrorEnvelope)
+    )
+)]
+#[get("/info")]
+pub async fn chain_info() -> impl Responder {
+    let Some(chain) = drand_chain() else {
+        return disabled();
+    };
+
+    HttpResponse::Ok()
+        .json(chain.info())
+}
+
+#[utoipa::path(
+    summary = "Latest round",
+    responses(
+        (status = 200, body = RoundDocument),
+        (status = 404, description = "The chain is disabled or the round isn't available", body = ErrorEnvelope)
+    )
+)]
+#[get("/public/latest")]
+pub async fn latest_round() -> impl Responder {
+    let Some(chain) = drand_chain() else {
+        return disabled();
+    };
+
+    round_response(grv!(Round::latest().await), latest_cache_control(chain))
+}
+
+#[utoipa::path(
+    summary = "Round by number, 0 returns the latest one",
+    responses(
+        (status = 200, body = RoundDocument),
+        (status = 404, description = "The chain is disabled or the round isn't available", body = ErrorEnvelope)
+    )
+)]
+#[get("/public/{round}")]
+pub async fn round_by_number(round: Path<i64>) -> impl Responder {
+    let Some(chain) = drand_chain() else {
+        return disabled();
+    };
+
+    // drand clients ask for round 0 to get the latest one.
+    if *round == 0 {
+        return round_response(grv!(Round::latest().await), latest_cache_control(chain));
+    }
+
+    round_response(
+        grv!(Round::from_round(*round).await),
+        "public, max-age=31536000, immutable".into()
+    )
+}
@@ -0,0 +1,52 @@
+use actix_web::{get, post, web::{Json, Path}, Responder};
+use utoipa::OpenApi;
+use crate::{frame_bytes, grv, require_key, helpers::http::{context::record_body, errors::{ApiError, ErrorEnvelope}, responses::TimedResponse}, models::{draw::{Draw, DrawOptions}, key::MaybeApiKey}};
+
+#[derive(OpenApi)]
+#[openapi(paths(create_draw, get_draw))]
+pub struct DrawsApi;
+
+#[utoipa::path(
+    summary = "Commit to a draw performed at draw_at",
+    responses(
+        (status = 200, body = TimedResponse<Draw>),
+        (status = 400, body = ErrorEnvelope),
+        (status = 401, body = ErrorEnvelope)
+    ),
+    security(("api_key" = []))
+)]
+#[post("")]
+pub async fn create_draw(key: MaybeApiKey, options: Json<DrawOptions>) -> impl Responder {
+    let key = require_key!(key, "An api key is required to create draws.");
+
+    record_body(&*options);
+
+    match Draw::new(key.id, options.into_inner()).await {
+        Ok(draw) => TimedResponse::new(draw, Some(key))
+            .into(),
+        Err(error) => ApiError::from(error)
+            .into()
+    }
+}
+
+#[utoipa::path(
+    summary = "Draw by id, performed at draw_at",
+    responses(
+        (status = 200, body = TimedResponse<Draw>),
+        (status = 404, body = ErrorEnvelope)
+    )
+)]
+#[get("/{id}")]
+pub async fn get_draw(id: Path<i32>) -> impl Responder {
+    let Some(mut draw) = grv!(Draw::from_id(*id).await) else {
+        return ApiError::not_found("draw_not_found", "The draw doesn't exist.")
+            .into();
+    };
+
+    if draw.is_due() {
+        draw = grv!(draw.perform(&frame_bytes!()).await);
+    }
+
+    TimedResponse::new(draw, None)
+        .into()
+}
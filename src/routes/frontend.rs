@@ -0,0 +1,144 @@
+use std::{borrow::Cow, path::{Component, Path}};
+use actix_web::{http::{header::{AcceptEncoding, ContentEncoding, Encoding, Header, CACHE_CONTROL, CONTENT_ENCODING, VARY}, Method}, web::Bytes, HttpRequest, HttpResponse};
+use mime_guess::from_path;
+use tokio::fs::read;
+use crate::helpers::{http::errors::route_not_found, misc::config::config};
+#[cfg(feature = "embed")]
+use rust_embed::RustEmbed;
+
+pub const API_PREFIX: &str = "/api";
+
+// vite puts every hashed file under assets/, their content never changes for a name.
+const HASHED_PREFIX: &str = "assets/";
+const HASHED_CACHE: &str = "public, max-age=31536000, immutable";
+
+enum Bundle {
+    Directory(&'static Path),
+    #[cfg(feature = "embed")]
+    Embedded
+}
+
+// the bundle has to be built with `deno task build` before compiling with embed.
+#[cfg(feature = "embed")]
+#[derive(RustEmbed)]
+#[folder = "frontend/dist"]
+struct EmbeddedBundle;
+
+fn precompressed_extension(encoding: &Encoding) -> Option<&'static str> {
+    match encoding {
+        Encoding::Known(ContentEncoding::Brotli) => Some("br"),
+        Encoding::Known(ContentEncoding::Gzip) => Some("gz"),
+        _ => None
+    }
+}
+
+impl Bundle {
+    // a configured directory wins over the embedded bundle so it can be replaced without a rebuild.
+    fn configured() -> Option<Self> {
+        match &config().frontend.path {
+            Some(path) => Some(Self::Directory(path)),
+            #[cfg(feature = "embed")]
+            None => Some(Self::Embedded),
+            #[cfg(not(feature = "embed"))]
+            None => None
+        }
+    }
+
+    async fn read(&self, path: &str) -> Option<Cow<'static, [u8]>> {
+        match self {
+            Self::Directory(root) => read(root.join(path))
+                .await
+                .ok()
+                .map(Cow::Owned),
+            #[cfg(feature = "embed")]
+            Self::Embedded => EmbeddedBundle::get(path)
+                .map(|file| file.data)
+        }
+    }
+
+    // precompressed variants are tried in the client's order of preference,
+    // falling back to the plain file when none of them exist.
+    async fn respond(&self, req: &HttpRequest, path: &str) -> Option<HttpResponse> {
+        let accepted = AcceptEncoding::parse(req)
+            .unwrap_or(AcceptEncoding(Vec::new()));
+
+        let mut supported = vec![Encoding::brotli(), Encoding::gzip(), Encoding::identity()];
+
+        while let Some(encoding) = accepted.negotiate(supported.iter()) {
+            let Some(extension) = precompressed_extension(&encoding) else {
+                return Some(file_response(path, None, self.read(path).await?));
+            };
+
+            match self.read(&format!("{path}.{extension}")).await {
+                Some(data) => return Some(file_response(path, Some(encoding), data)),
+                None => supported.retain(|supported| *supported != encoding)
+            }
+        }
+
+        None
+    }
+}
+
+fn file_response(path: &str, encoding: Option<Encoding>, data: Cow<'static, [u8]>) -> HttpResponse {
+    let cache = match path.starts_with(HASHED_PREFIX) {
+        true => HASHED_CACHE,
+        false => "no-cache"
+    };
+
+    let mut response = HttpResponse::Ok();
+
+    response
+        .content_type(from_path(path).first_or_octet_stream().as_ref())
+        .insert_header((CACHE_CONTROL, cache))
+        .insert_header((VARY, "Accept-Encoding"));
+
+    if let Some(encoding) = encoding {
+        response.insert_header((CONTENT_ENCODING, encoding.to_string()));
+    }
+
+    response.body(match data {
+        Cow::Borrowed(data) => Bytes::from_static(data),
+        Cow::Owned(data) => Bytes::from(data)
+    })
+}
+
+// paths without an extension are client side routes and get the index,
+// anything else that isn't in the bundle is a 404.
+pub async fn serve_frontend(req: HttpRequest) -> HttpResponse {
+    let Some(bundle) = Bundle::configured() else {
+        return route_not_found().await;
+    };
+
+    // nested api scopes fall back to the app's default service as well.
+    let api = req
+        .path()
+        .strip_prefix(API_PREFIX)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'));
+
+    if api || (req.method() != Method::GET && req.method() != Method::HEAD) {
+        return route_not_found().await;
+    }
+
+    let path = match req.path().trim_start_matches('/') {
+        "" => "index.html",
+        path => path
+    };
+
+    let path = Path::new(path);
+
+    if !path.components().all(|component| matches!(component, Component::Normal(_))) {
+        return route_not_found().await;
+    }
+
+    if let Some(response) = bundle.respond(&req, &path.to_string_lossy()).await {
+        return response;
+    }
+
+    if path.extension().is_none() {
+        if let Some(response) = bundle.respond(&req, "index.html").await {
+            return response;
+        }
+    }
+
+    route_not_found().await
+}
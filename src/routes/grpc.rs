@@ -0,0 +1,246 @@
+use std::{net::SocketAddr, pin::Pin};
+use actix_web::web::Bytes;
+use futures_util::{stream::unfold, Stream};
+use log::{error, info, warn};
+use time::OffsetDateTime;
+use tokio::time::Instant;
+use tonic::{async_trait, transport::Server, Request, Response, Status};
+use crate::{helpers::{generator::{frame::frame_bytes_from_source, random::{get_bool, get_ranged_wide, get_signed, get_unsigned}, stream::FRAME_LIFETIME}, misc::color::{Color, ColorError}}, models::key::{ApiKey, QuotaError}};
+use proto::{generator_server::{Generator, GeneratorServer}, BoolRequest, BoolResponse, BytesRequest, BytesResponse, ColorRequest, ColorResponse, RangeRequest, RangeResponse, SignedRequest, SignedResponse, StreamBytesRequest, UnsignedRequest, UnsignedResponse};
+
+mod proto {
+    tonic::include_proto!("rlarndg.v1");
+}
+
+const MAX_BYTES: u32 = 65_536;
+const MAX_CHUNKS: u32 = 10_000;
+
+pub struct GeneratorService;
+
+struct ChunkStream {
+    key: ApiKey,
+    size: u32,
+    remaining: u32,
+    frame: Option<(Bytes, Instant)>
+}
+
+impl From<QuotaError> for Status {
+    fn from(error: QuotaError) -> Self {
+        Status::resource_exhausted(error.to_string())
+    }
+}
+
+impl From<ColorError> for Status {
+    fn from(error: ColorError) -> Self {
+        Status::invalid_argument(error.to_string())
+    }
+}
+
+pub async fn run_grpc(address: SocketAddr) {
+    info!("Serving gRPC on {address}.");
+
+    let served = Server::builder()
+        .add_service(GeneratorServer::new(GeneratorService))
+        .serve(address)
+        .await;
+
+    if let Err(error) = served {
+        error!("The gRPC server stopped, an error occurred: {error:#}");
+    }
+}
+
+// the key is read from the `authorization` metadata, as the Authorization header over http.
+async fn authenticate<T>(request: &Request<T>) -> Result<ApiKey, Status> {
+    let token = request
+        .metadata()
+        .get("authorization")
+        .ok_or_else(|| Status::unauthenticated("An api key is required, pass it as the `authorization` metadata."))?
+        .to_str()
+        .map_err(|_| Status::unauthenticated("The provided key is not valid."))?;
+
+    ApiKey::from_key(token.to_string())
+        .await
+        .map_err(|error| {
+            error!("Couldn't verify a gRPC api key, an error occurred: {error:#}");
+            Status::internal("An internal error occurred.")
+        })?
+        .ok_or_else(|| Status::unauthenticated("The provided key is not valid."))
+}
+
+async fn frame() -> Result<Bytes, Status> {
+    frame_bytes_from_source()
+        .await
+        .map_err(|error| {
+            warn!("Couldn't capture a frame for a gRPC call, an error occurred: {error:#}");
+            Status::unavailable("The entropy source is unavailable, try again later.")
+        })
+}
+
+fn timestamp() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+fn check_size(size: u32) -> Result<u64, Status> {
+    match (1..=MAX_BYTES).contains(&size) {
+        true => Ok(size as u64 * 8),
+        false => Err(Status::invalid_argument(format!("The size must be between 1 and {MAX_BYTES} bytes.")))
+    }
+}
+
+fn random_bytes(frame: &Bytes, size: u32) -> Vec<u8> {
+    (0..size.div_ceil(4))
+        .flat_map(|_| get_unsigned(frame).to_be_bytes())
+        .take(size as usize)
+        .collect()
+}
+
+impl ChunkStream {
+    async fn next(&mut self) -> Result<BytesResponse, Status> {
+        let frame = match &self.frame {
+            Some((frame, captured)) if captured.elapsed() < FRAME_LIFETIME => frame.clone(),
+            _ => {
+                let frame = frame().await?;
+                self.frame = Some((frame.clone(), Instant::now()));
+                frame
+            }
+        };
+
+        self.key.consume_bits(self.size as u64 * 8)?;
+        self.remaining -= 1;
+
+        Ok(BytesResponse {
+            value: random_bytes(&frame, self.size),
+            timestamp: timestamp()
+        })
+    }
+}
+
+#[async_trait]
+impl Generator for GeneratorService {
+    type StreamBytesStream = Pin<Box<dyn Stream<Item = Result<BytesResponse, Status>> + Send>>;
+
+    async fn unsigned(&self, request: Request<UnsignedRequest>) -> Result<Response<UnsignedResponse>, Status> {
+        authenticate(&request).await?.consume(32)?;
+
+        Ok(Response::new(UnsignedResponse {
+            value: get_unsigned(&frame().await?),
+            timestamp: timestamp()
+        }))
+    }
+
+    async fn signed(&self, request: Request<SignedRequest>) -> Result<Response<SignedResponse>, Status> {
+        authenticate(&request).await?.consume(32)?;
+
+        let frame = frame().await?;
+
+        Ok(Response::new(SignedResponse {
+            value: get_signed(&frame),
+            timestamp: timestamp()
+        }))
+    }
+
+    async fn bool(&self, request: Request<BoolRequest>) -> Result<Response<BoolResponse>, Status> {
+        authenticate(&request).await?.consume(1)?;
+
+        Ok(Response::new(BoolResponse {
+            value: get_bool(&frame().await?),
+            timestamp: timestamp()
+        }))
+    }
+
+    async fn color(&self, request: Request<ColorRequest>) -> Result<Response<ColorResponse>, Status> {
+        let key = authenticate(&request).await?;
+        let request = request.into_inner();
+
+        let background = match &request.background {
+            Some(background) => Some(background.parse::<Color>()?),
+            None if request.contrast.is_some() => Some(Color::new(255, 255, 255)),
+            None => None
+        };
+
+        key.consume(if request.alpha { 32 } else { 24 })?;
+
+        let frame = frame().await?;
+
+        let mut color = Color::from(get_unsigned(&frame));
+
+        if request.alpha {
+            color = color.with_alpha(get_unsigned(&frame) as u8);
+        }
+
+        if let Some(background) = background {
+            color = color.with_contrast(&background, request.contrast.unwrap_or(4.5))?;
+        }
+
+        Ok(Response::new(ColorResponse {
+            hex: color.as_hex(),
+            css: color.as_css(),
+            timestamp: timestamp()
+        }))
+    }
+
+    async fn bytes(&self, request: Request<BytesRequest>) -> Result<Response<BytesResponse>, Status> {
+        let key = authenticate(&request).await?;
+        let size = request.get_ref().size;
+
+        key.consume(check_size(size)?)?;
+
+        Ok(Response::new(BytesResponse {
+            value: random_bytes(&frame().await?, size),
+            timestamp: timestamp()
+        }))
+    }
+
+    async fn range(&self, request: Request<RangeRequest>) -> Result<Response<RangeResponse>, Status> {
+        let key = authenticate(&request).await?;
+        let RangeRequest { min, max } = request.into_inner();
+
+        if min > max {
+            return Err(Status::invalid_argument("The minimum can't be greater than the maximum."));
+        }
+
+        // the span only overflows when the whole i64 range is requested.
+        let span = max
+            .abs_diff(min)
+            .checked_add(1);
+
+        key.consume(span.map_or(64, |span| (64 - (span - 1).leading_zeros()) as u64))?;
+
+        let frame = frame().await?;
+
+        let offset = match span {
+            Some(span) => get_ranged_wide(&frame, span),
+            None => ((get_unsigned(&frame) as u64) << 32) | get_unsigned(&frame) as u64
+        };
+
+        Ok(Response::new(RangeResponse {
+            value: min.wrapping_add_unsigned(offset),
+            timestamp: timestamp()
+        }))
+    }
+
+    async fn stream_bytes(&self, request: Request<StreamBytesRequest>) -> Result<Response<Self::StreamBytesStream>, Status> {
+        let key = authenticate(&request).await?;
+        let StreamBytesRequest { size, count } = request.into_inner();
+
+        check_size(size)?;
+
+        if !(1..=MAX_CHUNKS).contains(&count) {
+            return Err(Status::invalid_argument(format!("The count must be between 1 and {MAX_CHUNKS} chunks.")));
+        }
+
+        key.consume(0)?;
+
+        // a failed chunk is sent as the stream's status and ends it.
+        let chunks = unfold(Some(ChunkStream { key, size, remaining: count, frame: None }), |state| async move {
+            let mut state = state.filter(|state| state.remaining > 0)?;
+
+            Some(match state.next().await {
+                Ok(chunk) => (Ok(chunk), Some(state)),
+                Err(status) => (Err(status), None)
+            })
+        });
+
+        Ok(Response::new(Box::pin(chunks)))
+    }
+}
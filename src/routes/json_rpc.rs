@@ -0,0 +1,446 @@
+use std::{collections::HashSet, fmt::Display};
+use actix_web::{post, web::Bytes, HttpResponse, Responder};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use log::error;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{from_value, json, Value};
+use time::{macros::format_description, OffsetDateTime};
+use utoipa::OpenApi;
+use crate::{helpers::{generator::{frame::frame_bytes_from_source, random::{get_float, get_ranged, get_ranged_wide, get_unsigned, get_uuid, shuffle}}, http::{context::current_request_id, json_rpc::{RpcError, RpcRequest, RpcResponse}}}, models::key::ApiKey};
+
+#[derive(OpenApi)]
+#[openapi(paths(invoke))]
+pub struct JsonRpcApi;
+
+const MAX_VALUES: u32 = 10_000;
+const MAX_UUIDS: u32 = 1_000;
+const MAX_BLOBS: u32 = 100;
+const MAX_BLOB_BITS: u32 = 1_048_576;
+const MAX_INTEGER: i64 = 1_000_000_000;
+const MAX_GAUSSIAN: i64 = 1_000_000;
+const MAX_STRING_LENGTH: u32 = 32;
+const MAX_CHARACTERS: usize = 128;
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_base() -> u32 {
+    10
+}
+
+fn default_blob_format() -> String {
+    "base64".into()
+}
+
+fn check_range(name: &'static str, value: i64, min: i64, max: i64) -> Result<(), RpcError> {
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(RpcError::OutOfRange { name, min, max })
+    }
+}
+
+fn check_float(name: &'static str, value: f64, min: i64, max: i64) -> Result<(), RpcError> {
+    if value.is_finite() && (min as f64..=max as f64).contains(&value) {
+        Ok(())
+    } else {
+        Err(RpcError::OutOfRange { name, min, max })
+    }
+}
+
+// bits needed to pick one value out of `range` possible ones.
+fn bits_for(range: u64) -> u64 {
+    (64 - range.saturating_sub(1).leading_zeros()) as u64
+}
+
+fn decimal_bits(digits: u32) -> u64 {
+    (digits as f64 * 10f64.log2()).ceil() as u64
+}
+
+fn internal(error: impl Display) -> RpcError {
+    error!(
+        "Request {} failed with a JSON-RPC internal error: {error:#}",
+        current_request_id().unwrap_or_else(|| "-".into())
+    );
+    RpcError::Internal
+}
+
+// values without replacement use Floyd's algorithm so the range is never
+// allocated, the result is shuffled as the algorithm doesn't pick the order.
+fn sample(frame: &Bytes, range: u64, n: u32, replacement: bool) -> Vec<u64> {
+    if replacement {
+        return (0..n)
+            .map(|_| get_ranged_wide(frame, range))
+            .collect();
+    }
+
+    let mut chosen = HashSet::new();
+    let mut values = Vec::new();
+
+    for upper in range - n as u64..range {
+        let value = get_ranged_wide(frame, upper + 1);
+        let value = if chosen.contains(&value) { upper } else { value };
+
+        chosen.insert(value);
+        values.push(value);
+    }
+
+    shuffle(frame, &mut values);
+
+    values
+}
+
+trait Generator: DeserializeOwned {
+    // validates the parameters and returns the bits the call will use.
+    fn bits(&self) -> Result<u64, RpcError>;
+
+    fn generate(&self, frame: &Bytes) -> Value;
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Integers {
+    n: u32,
+    min: i64,
+    max: i64,
+    #[serde(default = "default_true")]
+    replacement: bool,
+    #[serde(default = "default_base")]
+    base: u32
+}
+
+impl Generator for Integers {
+    fn bits(&self) -> Result<u64, RpcError> {
+        check_range("n", self.n as i64, 1, MAX_VALUES as i64)?;
+        check_range("min", self.min, -MAX_INTEGER, MAX_INTEGER)?;
+        check_range("max", self.max, -MAX_INTEGER, MAX_INTEGER)?;
+
+        if self.min > self.max {
+            return Err(RpcError::MinGreaterThanMax);
+        }
+
+        if ![2, 8, 10, 16].contains(&self.base) {
+            return Err(RpcError::Malformed("base"));
+        }
+
+        let range = (self.max - self.min + 1) as u64;
+
+        if !self.replacement && self.n as u64 > range {
+            return Err(RpcError::NotEnoughValues { requested: self.n as u64, possible: range });
+        }
+
+        Ok(self.n as u64 * bits_for(range))
+    }
+
+    fn generate(&self, frame: &Bytes) -> Value {
+        let range = (self.max - self.min + 1) as u64;
+
+        sample(frame, range, self.n, self.replacement)
+            .into_iter()
+            .map(|value| {
+                let value = self.min + value as i64;
+                let sign = if value < 0 { "-" } else { "" };
+                let magnitude = value.unsigned_abs();
+
+                match self.base {
+                    2 => json!(format!("{sign}{magnitude:b}")),
+                    8 => json!(format!("{sign}{magnitude:o}")),
+                    16 => json!(format!("{sign}{magnitude:x}")),
+                    _ => json!(value)
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DecimalFractions {
+    n: u32,
+    decimal_places: u32,
+    #[serde(default = "default_true")]
+    replacement: bool
+}
+
+impl Generator for DecimalFractions {
+    fn bits(&self) -> Result<u64, RpcError> {
+        check_range("n", self.n as i64, 1, MAX_VALUES as i64)?;
+        check_range("decimalPlaces", self.decimal_places as i64, 1, 14)?;
+
+        let range = 10u64.pow(self.decimal_places);
+
+        if !self.replacement && self.n as u64 > range {
+            return Err(RpcError::NotEnoughValues { requested: self.n as u64, possible: range });
+        }
+
+        Ok(self.n as u64 * decimal_bits(self.decimal_places))
+    }
+
+    fn generate(&self, frame: &Bytes) -> Value {
+        let range = 10u64.pow(self.decimal_places);
+
+        sample(frame, range, self.n, self.replacement)
+            .into_iter()
+            .map(|value| json!(value as f64 / range as f64))
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Gaussians {
+    n: u32,
+    mean: f64,
+    standard_deviation: f64,
+    significant_digits: u32
+}
+
+impl Generator for Gaussians {
+    fn bits(&self) -> Result<u64, RpcError> {
+        check_range("n", self.n as i64, 1, MAX_VALUES as i64)?;
+        check_float("mean", self.mean, -MAX_GAUSSIAN, MAX_GAUSSIAN)?;
+        check_float("standardDeviation", self.standard_deviation, -MAX_GAUSSIAN, MAX_GAUSSIAN)?;
+        check_range("significantDigits", self.significant_digits as i64, 2, 14)?;
+
+        Ok(self.n as u64 * decimal_bits(self.significant_digits))
+    }
+
+    fn generate(&self, frame: &Bytes) -> Value {
+        (0..self.n)
+            .map(|_| {
+                // box-muller transform, the first value is kept away from zero.
+                let radius = (-2.0 * (1.0 - get_float(frame)).ln()).sqrt();
+                let angle = 2.0 * std::f64::consts::PI * get_float(frame);
+                let value = self.mean + self.standard_deviation * radius * angle.cos();
+
+                let rounded = format!("{:.*e}", self.significant_digits as usize - 1, value)
+                    .parse::<f64>()
+                    .unwrap_or(value);
+
+                json!(rounded)
+            })
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Strings {
+    n: u32,
+    length: u32,
+    characters: String,
+    #[serde(default = "default_true")]
+    replacement: bool
+}
+
+impl Strings {
+    fn characters(&self) -> Vec<char> {
+        self.characters
+            .chars()
+            .collect()
+    }
+}
+
+impl Generator for Strings {
+    fn bits(&self) -> Result<u64, RpcError> {
+        let characters = self.characters();
+
+        check_range("n", self.n as i64, 1, MAX_VALUES as i64)?;
+        check_range("length", self.length as i64, 1, MAX_STRING_LENGTH as i64)?;
+        check_range("characters", characters.len() as i64, 1, MAX_CHARACTERS as i64)?;
+
+        // repeated characters don't add possible strings.
+        let distinct = characters
+            .iter()
+            .collect::<HashSet<_>>()
+            .len();
+
+        let possible = (distinct as u64)
+            .checked_pow(self.length)
+            .unwrap_or(u64::MAX);
+
+        if !self.replacement && self.n as u64 > possible {
+            return Err(RpcError::NotEnoughValues { requested: self.n as u64, possible });
+        }
+
+        Ok(self.n as u64 * self.length as u64 * bits_for(characters.len() as u64))
+    }
+
+    fn generate(&self, frame: &Bytes) -> Value {
+        let characters = self.characters();
+        let mut seen = HashSet::new();
+        let mut values = Vec::new();
+
+        while values.len() < self.n as usize {
+            let value = (0..self.length)
+                .map(|_| characters[get_ranged(frame, characters.len() as u32) as usize])
+                .collect::<String>();
+
+            if self.replacement || seen.insert(value.clone()) {
+                values.push(json!(value));
+            }
+        }
+
+        Value::Array(values)
+    }
+}
+
+#[derive(Deserialize)]
+struct Uuids {
+    n: u32
+}
+
+impl Generator for Uuids {
+    fn bits(&self) -> Result<u64, RpcError> {
+        check_range("n", self.n as i64, 1, MAX_UUIDS as i64)?;
+
+        Ok(self.n as u64 * 122)
+    }
+
+    fn generate(&self, frame: &Bytes) -> Value {
+        (0..self.n)
+            .map(|_| json!(get_uuid(frame)))
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct Blobs {
+    n: u32,
+    size: u32,
+    #[serde(default = "default_blob_format")]
+    format: String
+}
+
+impl Generator for Blobs {
+    fn bits(&self) -> Result<u64, RpcError> {
+        check_range("n", self.n as i64, 1, MAX_BLOBS as i64)?;
+        check_range("size", self.size as i64, 1, MAX_BLOB_BITS as i64)?;
+
+        if self.size % 8 != 0 {
+            return Err(RpcError::Malformed("size"));
+        }
+
+        if !["base64", "hex"].contains(&self.format.as_str()) {
+            return Err(RpcError::Malformed("format"));
+        }
+
+        Ok(self.n as u64 * self.size as u64)
+    }
+
+    fn generate(&self, frame: &Bytes) -> Value {
+        (0..self.n)
+            .map(|_| {
+                let bytes = (0..self.size / 8)
+                    .map(|_| get_unsigned(frame) as u8)
+                    .collect::<Vec<_>>();
+
+                match self.format.as_str() {
+                    "hex" => json!(hex::encode(bytes)),
+                    _ => json!(STANDARD.encode(bytes))
+                }
+            })
+            .collect()
+    }
+}
+
+fn random_org_time(time: OffsetDateTime) -> Result<String, RpcError> {
+    time.format(format_description!("[year]-[month]-[day] [hour]:[minute]:[second]Z"))
+        .map_err(internal)
+}
+
+async fn generate<G: Generator>(key: &ApiKey, params: Value) -> Result<Value, RpcError> {
+    let generator = from_value::<G>(params)
+        .map_err(|error| RpcError::InvalidParams(error.to_string()))?;
+
+    let bits = generator.bits()?;
+    let usage = key.consume(bits)?;
+    let frame = frame_bytes_from_source()
+        .await
+        .map_err(internal)?;
+
+    Ok(json!({
+        "random": {
+            "data": generator.generate(&frame),
+            "completionTime": random_org_time(OffsetDateTime::now_utc())?
+        },
+        "bitsUsed": bits,
+        "bitsLeft": usage.bits_left,
+        "requestsLeft": usage.requests_left,
+        "advisoryDelay": 0
+    }))
+}
+
+async fn authenticate(params: &Value) -> Result<ApiKey, RpcError> {
+    let Some(token) = params.get("apiKey") else {
+        return Err(RpcError::InvalidParams("missing field `apiKey`".into()));
+    };
+
+    let Some(token) = token.as_str() else {
+        return Err(RpcError::Malformed("apiKey"));
+    };
+
+    ApiKey::from_key(token.to_string())
+        .await
+        .map_err(internal)?
+        .ok_or(RpcError::UnknownKey)
+}
+
+async fn call(request: RpcRequest) -> Result<Value, RpcError> {
+    let method = request.method.as_str();
+
+    if !matches!(
+        method,
+        "generateIntegers" | "generateDecimalFractions" | "generateGaussians"
+            | "generateStrings" | "generateUUIDs" | "generateBlobs" | "getUsage"
+    ) {
+        return Err(RpcError::MethodNotFound);
+    }
+
+    let key = authenticate(&request.params).await?;
+
+    match method {
+        "generateIntegers" => generate::<Integers>(&key, request.params).await,
+        "generateDecimalFractions" => generate::<DecimalFractions>(&key, request.params).await,
+        "generateGaussians" => generate::<Gaussians>(&key, request.params).await,
+        "generateStrings" => generate::<Strings>(&key, request.params).await,
+        "generateUUIDs" => generate::<Uuids>(&key, request.params).await,
+        "generateBlobs" => generate::<Blobs>(&key, request.params).await,
+        _ => {
+            let usage = key.usage();
+
+            Ok(json!({
+                "status": "running",
+                "creationTime": random_org_time(key.created_at)?,
+                "bitsLeft": usage.bits_left,
+                "requestsLeft": usage.requests_left,
+                "totalBits": usage.total_bits,
+                "totalRequests": usage.total_requests
+            }))
+        }
+    }
+}
+
+// errors are part of the JSON-RPC response so the status is always 200.
+#[utoipa::path(
+    summary = "random.org JSON-RPC 4 compatible methods, authenticated by the apiKey param",
+    request_body = RpcRequest,
+    responses(
+        (status = 200, body = RpcResponse)
+    )
+)]
+#[post("/invoke")]
+pub async fn invoke(body: Bytes) -> impl Responder {
+    let response = match RpcRequest::parse(&body) {
+        Ok(request) => {
+            let id = request.id.clone();
+
+            RpcResponse::new(id, call(request).await)
+        },
+        Err(error) => RpcResponse::new(Value::Null, Err(error))
+    };
+
+    HttpResponse::Ok()
+        .json(response)
+}
@@ -1,6 +1,11 @@
 use actix_web::{get, post, web::Query, HttpRequest, HttpResponse, Responder};
 use serde::Deserialize;
-use crate::{grv, helpers::misc::stripe::{create_stripe_payment, verify_payment}, models::{key::ApiKey, user::User}};
+use utoipa::{IntoParams, OpenApi};
+use crate::{grv, helpers::{http::errors::{ApiError, ErrorEnvelope}, misc::stripe::{create_stripe_payment, verify_payment}}, models::{key::ApiKey, user::User}};
+
+#[derive(OpenApi)]
+#[openapi(paths(get_key_ids, reset_key, pay_new_key, handle_success_payment))]
+pub struct KeysApi;
 
 fn get_callback(req: &HttpRequest) -> String {
     let connection_info = req
@@ -13,33 +18,56 @@ fn get_callback(req: &HttpRequest) -> String {
     )
 }
 
+#[utoipa::path(
+    summary = "Ids of the logged in user's keys",
+    responses(
+        (status = 200, body = Vec<i32>)
+    ),
+    security(("auth_cookie" = []))
+)]
 #[get("/user")]
 pub async fn get_key_ids(user: User) -> impl Responder {
     HttpResponse::Ok()
         .json(
-            &grv!(ApiKey::user_keys(user.id).await)
+            grv!(ApiKey::user_keys(user.id).await)
                 .iter()
                 .map(|k| k.id)
                 .collect::<Vec<_>>()
         )
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 struct ResetKeyQuery {
     id: i32
 }
 
+#[utoipa::path(
+    summary = "Generate a new token for a key",
+    params(ResetKeyQuery),
+    responses(
+        (status = 200, content_type = "text/plain", description = "The new token", body = String)
+    ),
+    security(("auth_cookie" = []))
+)]
 #[post("/reset")]
 pub async fn reset_key(user: User, query: Query<ResetKeyQuery>) -> impl Responder {
     HttpResponse::Ok()
         .body(grv!(ApiKey::reset_key(user.id, query.id).await))
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 struct PaymentQuery {
     amount: f32
 }
 
+#[utoipa::path(
+    summary = "Redirect to a checkout session for a new key",
+    params(PaymentQuery),
+    responses(
+        (status = 308, description = "Redirects to the checkout")
+    ),
+    security(("auth_cookie" = []))
+)]
 #[get("/checkout")]
 pub async fn pay_new_key(user: User, req: HttpRequest, query: Query<PaymentQuery>) -> impl Responder {
     HttpResponse::PermanentRedirect()
@@ -50,7 +78,7 @@ pub async fn pay_new_key(user: User, req: HttpRequest, query: Query<PaymentQuery
         .finish()
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 struct CheckoutSuccess {
     #[serde(rename = "i")]
     user_id: i32,
@@ -60,11 +88,19 @@ struct CheckoutSuccess {
     checkout: String
 }
 
+#[utoipa::path(
+    summary = "Checkout callback, creates the paid key",
+    params(CheckoutSuccess),
+    responses(
+        (status = 308, description = "Redirects to the transaction success page"),
+        (status = 400, body = ErrorEnvelope)
+    )
+)]
 #[get("/checkout/success")]
 pub async fn handle_success_payment(req: HttpRequest, query: Query<CheckoutSuccess>) -> impl Responder {
     if !grv!(verify_payment(&query.checkout).await) {
-        return HttpResponse::BadRequest()
-            .body("Unknown or invalid transaction.");
+        return ApiError::bad_request("invalid_transaction", "Unknown or invalid transaction.")
+            .into();
     }
 
     let _ = grv!(ApiKey::new(query.user_id, &query.checkout, query.paid).await);
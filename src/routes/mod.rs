@@ -2,3 +2,17 @@
 pub mod values;
 pub mod auth;
 pub mod keys;
+pub mod decks;
+pub mod draws;
+pub mod beacon;
+pub mod signatures;
+pub mod receipts;
+pub mod archive;
+pub mod json_rpc;
+pub mod drand;
+pub mod v1;
+pub mod docs;
+pub mod stream;
+pub mod frontend;
+#[cfg(feature = "grpc")]
+pub mod grpc;
@@ -0,0 +1,33 @@
+use actix_web::{get, web::Path, HttpResponse, Responder};
+use utoipa::OpenApi;
+use crate::{grv, helpers::http::errors::{ApiError, ErrorEnvelope}, models::{key::MaybeApiKey, receipt::Receipt}};
+
+#[derive(OpenApi)]
+#[openapi(paths(get_receipt))]
+pub struct ReceiptsApi;
+
+#[utoipa::path(
+    summary = "Receipt by id, only readable by the key that issued it",
+    responses(
+        (status = 200, body = Receipt),
+        (status = 404, body = ErrorEnvelope)
+    ),
+    security((), ("api_key" = []))
+)]
+#[get("/{id}")]
+pub async fn get_receipt(key: MaybeApiKey, id: Path<String>) -> impl Responder {
+    let receipt = grv!(Receipt::from_id(&id).await);
+
+    let key_id = match key {
+        MaybeApiKey::Authorized(key) => Some(key.id),
+        MaybeApiKey::Unauthorized => None
+    };
+
+    // anonymous receipts can be read by anyone that knows their id.
+    match receipt {
+        Some(receipt) if receipt.key_id.is_none() || receipt.key_id == key_id => HttpResponse::Ok()
+            .json(receipt),
+        _ => ApiError::not_found("receipt_not_found", "The receipt doesn't exist or belongs to another key.")
+            .into()
+    }
+}
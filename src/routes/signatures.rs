@@ -0,0 +1,61 @@
+use actix_web::{get, post, web::Json, HttpResponse, Responder};
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+use crate::{grv, helpers::{authentication::signing::verifying_key, http::{errors::{ApiError, ErrorEnvelope}, responses::TimedResponse}}};
+
+#[derive(Serialize, ToSchema)]
+struct PublicKey {
+    algorithm: &'static str,
+    public_key: String,
+    encoding: &'static str,
+    canonicalization: &'static str
+}
+
+#[derive(Serialize, ToSchema)]
+struct Verification {
+    valid: bool
+}
+
+#[utoipa::path(
+    tag = "signatures",
+    summary = "Key used to sign the responses",
+    responses(
+        (status = 200, body = PublicKey),
+        (status = 404, body = ErrorEnvelope)
+    )
+)]
+#[get("/.well-known/rlarndg-key")]
+pub async fn public_key() -> impl Responder {
+    let Some(key) = verifying_key() else {
+        return ApiError::not_found("signing_disabled", "This instance doesn't sign its responses.")
+            .into();
+    };
+
+    HttpResponse::Ok()
+        .json(PublicKey {
+            algorithm: "ed25519",
+            public_key: hex::encode(key.as_bytes()),
+            encoding: "hex",
+            canonicalization: "compact json with sorted keys, excluding the signature field"
+        })
+}
+
+#[utoipa::path(
+    tag = "signatures",
+    summary = "Check the signature of a response",
+    responses(
+        (status = 200, body = Verification),
+        (status = 404, body = ErrorEnvelope)
+    )
+)]
+#[post("/verify")]
+pub async fn verify_response(response: Json<TimedResponse<Value>>) -> impl Responder {
+    if verifying_key().is_none() {
+        return ApiError::not_found("signing_disabled", "This instance doesn't sign its responses.")
+            .into();
+    }
+
+    HttpResponse::Ok()
+        .json(Verification { valid: grv!(response.into_inner().verify()) })
+}
@@ -0,0 +1,137 @@
+use std::convert::Infallible;
+use actix_web::{get, http::header::{CACHE_CONTROL, UPGRADE}, rt::spawn, web::{Bytes, Payload, Query}, HttpRequest, HttpResponse, Responder};
+use actix_ws::{handle, CloseCode, CloseReason, Message, MessageStream, Session};
+use futures_util::stream::unfold;
+use serde::{Deserialize, Serialize};
+use serde_json::to_string;
+use tokio::select;
+use utoipa::{IntoParams, OpenApi};
+use crate::{require_key, helpers::{generator::stream::{StreamEnd, StreamKind, StreamValue, Subscription}, http::errors::{ApiError, ErrorEnvelope}}, models::key::MaybeApiKey};
+
+#[derive(OpenApi)]
+#[openapi(paths(random_stream), components(schemas(StreamEnd)))]
+pub struct StreamApi;
+
+#[derive(Deserialize, IntoParams)]
+struct StreamQuery {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    rate: Option<f64>
+}
+
+fn sse_event(event: &str, id: Option<u64>, data: &impl Serialize) -> Bytes {
+    let id = id
+        .map(|id| format!("id: {id}\n"))
+        .unwrap_or_default();
+
+    Bytes::from(format!(
+        "{id}event: {event}\ndata: {}\n\n",
+        to_string(data).unwrap_or_default()
+    ))
+}
+
+// the stream ends after the event explaining why.
+fn sse_response(subscription: Subscription) -> HttpResponse {
+    let events = unfold(Some(subscription), |subscription| async move {
+        let mut subscription = subscription?;
+
+        Some(match subscription.next().await {
+            Ok((id, value)) => (Ok::<_, Infallible>(sse_event("value", Some(id), &value)), Some(subscription)),
+            Err(end) => (Ok(sse_event("end", None, &end)), None)
+        })
+    });
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((CACHE_CONTROL, "no-cache"))
+        .insert_header(("X-Accel-Buffering", "no"))
+        .streaming(events)
+}
+
+async fn run_websocket(mut subscription: Subscription, mut session: Session, mut messages: MessageStream) {
+    let end = loop {
+        select! {
+            next = subscription.next() => match next {
+                Ok((_, value)) => {
+                    if session.text(to_string(&value).unwrap_or_default()).await.is_err() {
+                        return;
+                    }
+                },
+                Err(end) => break end
+            },
+            message = messages.recv() => match message {
+                Some(Ok(Message::Ping(bytes))) => {
+                    if session.pong(&bytes).await.is_err() {
+                        return;
+                    }
+                },
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => {
+                    let _ = session.close(None).await;
+                    return;
+                },
+                _ => {}
+            }
+        }
+    };
+
+    let code = match end.code() {
+        "quota_exhausted" => CloseCode::Policy,
+        _ => CloseCode::Error
+    };
+
+    let _ = session.text(to_string(&end).unwrap_or_default()).await;
+    let _ = session.close(Some(CloseReason {
+        code,
+        description: Some(end.code().into())
+    })).await;
+}
+
+#[utoipa::path(
+    summary = "Stream values over server-sent events, or a WebSocket when the request asks for an upgrade",
+    description = "Each value is sent as a `value` event, or a text message over WebSocket. \
+        When the key's quota is exhausted or the entropy source fails a final `end` event with a code \
+        and message is sent and the stream is closed.",
+    params(StreamQuery),
+    responses(
+        (status = 200, content_type = "text/event-stream", body = StreamValue),
+        (status = 101, description = "Switches to a WebSocket carrying the same values as JSON text messages"),
+        (status = 400, body = ErrorEnvelope),
+        (status = 401, body = ErrorEnvelope),
+        (status = 429, description = "The key's streams are at its throughput limit or its quota is exhausted", body = ErrorEnvelope)
+    ),
+    security(("api_key" = []))
+)]
+#[get("/stream")]
+pub async fn random_stream(req: HttpRequest, body: Payload, key: MaybeApiKey, query: Query<StreamQuery>) -> impl Responder {
+    let key = require_key!(key, "An api key is required to stream values.");
+
+    let subscription = query
+        .kind
+        .as_deref()
+        .unwrap_or("unsigned")
+        .parse::<StreamKind>()
+        .and_then(|kind| Subscription::open(key, kind, query.rate.unwrap_or(1.0)));
+
+    let subscription = match subscription {
+        Ok(subscription) => subscription,
+        Err(error) => return ApiError::from(error).into()
+    };
+
+    let upgrade = req
+        .headers()
+        .get(UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+
+    if !upgrade {
+        return sse_response(subscription);
+    }
+
+    match handle(&req, body) {
+        Ok((response, session, messages)) => {
+            spawn(run_websocket(subscription, session, messages));
+            response
+        },
+        Err(error) => error.error_response()
+    }
+}
@@ -0,0 +1,91 @@
+use actix_web::{web::ServiceConfig, Scope};
+use utoipa::OpenApi;
+use super::{archive::{get_segment, ArchiveApi}, auth::{get_user, login, signup, AuthApi}, beacon::{latest_pulse, pulse_at, pulse_by_index, BeaconApi}, decks::{create_deck, draw_cards, get_deck, reshuffle_deck, DecksApi}, draws::{create_draw, get_draw, DrawsApi}, drand::{chain_info, latest_round, round_by_number, DrandApi}, json_rpc::{invoke, JsonRpcApi}, keys::{get_key_ids, handle_success_payment, pay_new_key, reset_key, KeysApi}, receipts::{get_receipt, ReceiptsApi}, signatures::verify_response, stream::{random_stream, StreamApi}, values::{random_batch, random_bigint, random_bool, random_choice, random_color, random_datetime, random_dice, random_geo, random_palette, random_prime_number, random_seed, random_signed, random_unsigned, ValuesApi}};
+
+// mirrors the scopes registered below.
+#[derive(OpenApi)]
+#[openapi(
+    paths(super::signatures::verify_response),
+    nest(
+        (path = "/random", api = ValuesApi, tags = ["values"]),
+        (path = "/random", api = StreamApi, tags = ["stream"]),
+        (path = "/random", api = DecksApi, tags = ["decks"]),
+        (path = "/draws", api = DrawsApi, tags = ["draws"]),
+        (path = "/beacon", api = BeaconApi, tags = ["beacon"]),
+        (path = "/receipts", api = ReceiptsApi, tags = ["receipts"]),
+        (path = "/drand", api = DrandApi, tags = ["drand"]),
+        (path = "/json-rpc/4", api = JsonRpcApi, tags = ["json-rpc"]),
+        (path = "/archive", api = ArchiveApi, tags = ["archive"]),
+        (path = "/auth", api = AuthApi, tags = ["auth"]),
+        (path = "/keys", api = KeysApi, tags = ["keys"])
+    )
+)]
+pub struct V1Api;
+
+pub fn configure(config: &mut ServiceConfig) {
+    config
+        .service(verify_response)
+        .service(
+            Scope::new("/random")
+                .service(random_unsigned)
+                .service(random_signed)
+                .service(random_bool)
+                .service(random_color)
+                .service(random_palette)
+                .service(random_dice)
+                .service(random_geo)
+                .service(random_datetime)
+                .service(random_seed)
+                .service(random_choice)
+                .service(random_bigint)
+                .service(random_prime_number)
+                .service(random_stream)
+                .service(random_batch)
+                .service(create_deck)
+                .service(get_deck)
+                .service(draw_cards)
+                .service(reshuffle_deck)
+        )
+        .service(
+            Scope::new("/draws")
+                .service(create_draw)
+                .service(get_draw)
+        )
+        .service(
+            Scope::new("/beacon")
+                .service(latest_pulse)
+                .service(pulse_at)
+                .service(pulse_by_index)
+        )
+        .service(
+            Scope::new("/receipts")
+                .service(get_receipt)
+        )
+        .service(
+            Scope::new("/drand")
+                .service(chain_info)
+                .service(latest_round)
+                .service(round_by_number)
+        )
+        .service(
+            Scope::new("/json-rpc/4")
+                .service(invoke)
+        )
+        .service(
+            Scope::new("/archive")
+                .service(get_segment)
+        )
+        .service(
+            Scope::new("/auth")
+                .service(login)
+                .service(signup)
+                .service(get_user)
+        )
+        .service(
+            Scope::new("/keys")
+                .service(get_key_ids)
+                .service(reset_key)
+                .service(pay_new_key)
+                .service(handle_success_payment)
+        );
+}
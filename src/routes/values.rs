@@ -1,7 +1,35 @@
-use actix_web::{get, web::Query, HttpResponse, Responder};
-use serde::Deserialize;
-use crate::{frame_bytes, helpers::{generator::random::{get_bool, get_unsigned}, http::responses::TimedResponse, misc::color::Color}, models::key::MaybeApiKey};
+use std::time::Duration;
+use actix_web::{get, http::StatusCode, post, web::{Bytes, Json, Query}, Responder, ResponseError};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{from_value, json, Value};
+use utoipa::{IntoParams, OpenApi, ToSchema};
+use crate::{frame_bytes, require_key, helpers::{generator::{bigint::{cancellable, check_bits, random_bits, random_prime, BigIntFormat}, choice::{check_choice, choose, WeightedItem}, datetime::{DateTimeError, DateTimeFormat, DateTimeRange, FormattedDateTime}, dice::{DiceExpression, DiceRoll}, geo::{Coordinates, GeoArea, GeoError}, random::{get_bool, get_signed, get_unsigned, get_uuid}, seed::{check_outputs, Seed, SeedAlgorithm, SeedError}}, http::{context::record_body, errors::{ApiError, ErrorBody, ErrorEnvelope}, query::LiteralQuery, responses::TimedResponse}, misc::color::{Color, ColorError, ColorFormat, FormattedColor, PaletteScheme}}, models::key::{ApiKey, MaybeApiKey}};
 
+#[derive(OpenApi)]
+#[openapi(paths(
+    random_unsigned,
+    random_signed,
+    random_bool,
+    random_color,
+    random_palette,
+    random_dice,
+    random_geo,
+    random_datetime,
+    random_seed,
+    random_choice,
+    random_bigint,
+    random_prime_number,
+    random_batch
+))]
+pub struct ValuesApi;
+
+#[utoipa::path(
+    summary = "Random unsigned 32 bit integer",
+    responses(
+        (status = 200, body = TimedResponse<u32>)
+    ),
+    security((), ("api_key" = []))
+)]
 #[get("/unsigned")]
 pub async fn random_unsigned(key: MaybeApiKey) -> impl Responder {
     TimedResponse::new(
@@ -11,20 +39,29 @@ pub async fn random_unsigned(key: MaybeApiKey) -> impl Responder {
         .into()
 }
 
+#[utoipa::path(
+    summary = "Random signed 32 bit integer",
+    responses(
+        (status = 200, body = TimedResponse<i32>)
+    ),
+    security((), ("api_key" = []))
+)]
 #[get("/signed")]
 pub async fn random_signed(key: MaybeApiKey) -> impl Responder {
-    let bytes = frame_bytes!();
-
-    let mut number = get_unsigned(&bytes) as i32;
-
-    if get_bool(&bytes) {
-        number = -number;
-    }
-
-    TimedResponse::new(number, key.into())
+    TimedResponse::new(
+        get_signed(&frame_bytes!()),
+        key.into()
+    )
         .into()
 }
 
+#[utoipa::path(
+    summary = "Random boolean",
+    responses(
+        (status = 200, body = TimedResponse<bool>)
+    ),
+    security((), ("api_key" = []))
+)]
 #[get("/boolean")]
 pub async fn random_bool(key: MaybeApiKey) -> impl Responder {
     let bytes = frame_bytes!();
@@ -36,34 +73,707 @@ pub async fn random_bool(key: MaybeApiKey) -> impl Responder {
         .into()
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 struct ColorQuery {
-    format: Option<String>
+    format: Option<String>,
+    alpha: Option<bool>,
+    background: Option<String>,
+    contrast: Option<f64>
+}
+
+impl ColorQuery {
+    fn format(&self) -> Result<ColorFormat, ColorError> {
+        self.format
+            .as_deref()
+            .unwrap_or("rgb")
+            .parse()
+    }
+
+    fn color(&self, bytes: &Bytes) -> Color {
+        let color = Color::from(get_unsigned(bytes));
+
+        match self.alpha {
+            Some(true) => color.with_alpha(get_unsigned(bytes) as u8),
+            _ => color
+        }
+    }
+
+    fn background(&self) -> Result<Option<Color>, ColorError> {
+        match &self.background {
+            Some(background) => Ok(Some(background.parse()?)),
+            None if self.contrast.is_some() => Ok(Some(Color::new(255, 255, 255))),
+            None => Ok(None)
+        }
+    }
+
+    fn constrain(&self, color: Color) -> Result<Color, ColorError> {
+        match self.background()? {
+            Some(background) => color.with_contrast(&background, self.contrast.unwrap_or(4.5)),
+            None => Ok(color)
+        }
+    }
+
+    fn bits(&self) -> u64 {
+        match self.alpha {
+            Some(true) => 32,
+            _ => 24
+        }
+    }
 }
 
+#[utoipa::path(
+    summary = "Random color, optionally constrained to a contrast ratio",
+    params(ColorQuery),
+    responses(
+        (status = 200, body = TimedResponse<FormattedColor>),
+        (status = 400, body = ErrorEnvelope)
+    ),
+    security((), ("api_key" = []))
+)]
 #[get("/color")]
 pub async fn random_color(query: Query<ColorQuery>, key: MaybeApiKey) -> impl Responder {
     let bytes = frame_bytes!();
 
-    let color = Color::from(get_unsigned(&bytes));
-
-    let format = query
-        .format
-        .clone()
-        .unwrap_or("rgb".to_string());
-
-    match format.as_str() {
-        "rgb" => {
-            TimedResponse::new(color, key.into())
-                .into()
-        },
-        "hex" => {
-            TimedResponse::new(color.as_hex(), key.into())
-                .into()
-        },
-        _ => {
-            HttpResponse::BadRequest()
-                .body("Invalid format parameter, expected or either rgb or hex.")
+    let color = query
+        .format()
+        .and_then(|format| Ok(
+            query
+                .constrain(query.color(&bytes))?
+                .formatted(format)
+        ));
+
+    match color {
+        Ok(color) => TimedResponse::new(color, key.into())
+            .into(),
+        Err(error) => ApiError::from(error)
+            .into()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+struct PaletteQuery {
+    size: Option<usize>,
+    scheme: Option<String>
+}
+
+impl PaletteQuery {
+    fn scheme(&self) -> Result<PaletteScheme, ColorError> {
+        self.scheme
+            .as_deref()
+            .unwrap_or("analogous")
+            .parse()
+    }
+
+    fn size(&self, scheme: PaletteScheme) -> usize {
+        self.size
+            .unwrap_or(scheme.default_size())
+            .clamp(1, 16)
+    }
+}
+
+#[utoipa::path(
+    summary = "Random color palette following a color scheme",
+    params(PaletteQuery, ColorQuery),
+    responses(
+        (status = 200, body = TimedResponse<Vec<FormattedColor>>),
+        (status = 400, body = ErrorEnvelope)
+    ),
+    security((), ("api_key" = []))
+)]
+#[get("/palette")]
+pub async fn random_palette(query: Query<PaletteQuery>, color: Query<ColorQuery>, key: MaybeApiKey) -> impl Responder {
+    let bytes = frame_bytes!();
+
+    let palette = query
+        .scheme()
+        .and_then(|scheme| {
+            let format = color.format()?;
+
+            color
+                .color(&bytes)
+                .palette(scheme, query.size(scheme))
+                .into_iter()
+                .map(|value| Ok(color.constrain(value)?.formatted(format)))
+                .collect::<Result<Vec<_>, ColorError>>()
+        });
+
+    match palette {
+        Ok(palette) => TimedResponse::new(palette, key.into())
+            .into(),
+        Err(error) => ApiError::from(error)
+            .into()
+    }
+}
+
+// `+` is part of the notation, so it's read as a literal query.
+#[derive(Deserialize, IntoParams)]
+struct DiceQuery {
+    #[param(example = "4d6kh3+2")]
+    roll: String
+}
+
+#[utoipa::path(
+    summary = "Roll dice using dice notation",
+    params(DiceQuery),
+    responses(
+        (status = 200, body = TimedResponse<DiceRoll>),
+        (status = 400, body = ErrorEnvelope)
+    ),
+    security((), ("api_key" = []))
+)]
+#[get("/dice")]
+pub async fn random_dice(query: LiteralQuery<DiceQuery>, key: MaybeApiKey) -> impl Responder {
+    match query.roll.parse::<DiceExpression>() {
+        Ok(expression) => TimedResponse::new(
+            expression.roll(&frame_bytes!()),
+            key.into()
+        )
+            .into(),
+        Err(error) => ApiError::from(error)
+            .into()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+struct GeoQuery {
+    count: Option<usize>,
+    format: Option<String>,
+    min_lat: Option<f64>,
+    max_lat: Option<f64>,
+    min_lon: Option<f64>,
+    max_lon: Option<f64>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    radius: Option<f64>
+}
+
+impl GeoQuery {
+    fn area(&self) -> Result<GeoArea, GeoError> {
+        let bounding_box = (self.min_lat, self.max_lat, self.min_lon, self.max_lon);
+        let circle = (self.lat, self.lon, self.radius);
+
+        match (bounding_box, circle) {
+            ((None, None, None, None), (None, None, None)) => Ok(GeoArea::Globe),
+            ((Some(min_lat), Some(max_lat), Some(min_lon), Some(max_lon)), (None, None, None)) => {
+                GeoArea::bounding_box(min_lat, max_lat, min_lon, max_lon)
+            },
+            ((None, None, None, None), (Some(lat), Some(lon), Some(radius))) => {
+                GeoArea::circle(Coordinates::new(lat, lon)?, radius)
+            },
+            ((None, None, None, None), _) | (_, (None, None, None)) => Err(GeoError::IncompleteArea),
+            _ => Err(GeoError::ConflictingArea)
+        }
+    }
+
+    fn parse(&self) -> Result<(GeoArea, bool), ApiError> {
+        let area = self.area()?;
+
+        let geojson = match self.format.as_deref() {
+            None | Some("json") => false,
+            Some("geojson") => true,
+            Some(_) => return Err(ApiError::bad_request("invalid_format", "Invalid format parameter, expected either json or geojson."))
+        };
+
+        Ok((area, geojson))
+    }
+
+    fn points(&self, area: &GeoArea, geojson: bool, bytes: &Bytes) -> Value {
+        let points = (0..self.count())
+            .map(|_| area.random_point(bytes))
+            .collect::<Vec<_>>();
+
+        match (self.count, geojson) {
+            (None, false) => json!(points[0]),
+            (Some(_), false) => json!(points),
+            (None, true) => points[0].as_geojson(),
+            (Some(_), true) => json!({
+                "type": "FeatureCollection",
+                "features": points
+                    .iter()
+                    .map(Coordinates::as_geojson)
+                    .collect::<Vec<Value>>()
+            })
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.count.unwrap_or(1).clamp(1, 100)
+    }
+}
+
+#[utoipa::path(
+    summary = "Random coordinates, a list of them when count is set or GeoJSON with format=geojson",
+    params(GeoQuery),
+    responses(
+        (status = 200, body = TimedResponse<Coordinates>),
+        (status = 400, body = ErrorEnvelope)
+    ),
+    security((), ("api_key" = []))
+)]
+#[get("/geo")]
+pub async fn random_geo(query: Query<GeoQuery>, key: MaybeApiKey) -> impl Responder {
+    let (area, geojson) = match query.parse() {
+        Ok(parsed) => parsed,
+        Err(error) => return error.into()
+    };
+
+    TimedResponse::new(
+        query.points(&area, geojson, &frame_bytes!()),
+        key.into()
+    )
+        .into()
+}
+
+#[derive(Deserialize, IntoParams)]
+struct DateTimeQuery {
+    from: Option<String>,
+    to: Option<String>,
+    format: Option<String>,
+    offset: Option<String>,
+    date: Option<bool>,
+    count: Option<usize>
+}
+
+impl DateTimeQuery {
+    fn parse(&self) -> Result<(DateTimeRange, DateTimeFormat), DateTimeError> {
+        let range = DateTimeRange::new(
+            self.from.as_deref(),
+            self.to.as_deref(),
+            self.offset.as_deref(),
+            self.date.unwrap_or(false)
+        )?;
+
+        Ok((range, DateTimeFormat::parse(self.format.as_deref())?))
+    }
+
+    // a single value is returned as is, while `count` always returns a list.
+    fn values(&self, range: &DateTimeRange, format: &DateTimeFormat, bytes: &Bytes) -> Result<Value, DateTimeError> {
+        let mut values = (0..self.count())
+            .map(|_| range.format(range.random(bytes), format))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(match self.count {
+            Some(_) => json!(values),
+            None => json!(values.remove(0))
+        })
+    }
+
+    fn count(&self) -> usize {
+        self.count.unwrap_or(1).clamp(1, 100)
+    }
+}
+
+#[utoipa::path(
+    summary = "Random date or time within a range, a list of them when count is set",
+    params(DateTimeQuery),
+    responses(
+        (status = 200, body = TimedResponse<FormattedDateTime>),
+        (status = 400, body = ErrorEnvelope)
+    ),
+    security((), ("api_key" = []))
+)]
+#[get("/datetime")]
+pub async fn random_datetime(query: LiteralQuery<DateTimeQuery>, key: MaybeApiKey) -> impl Responder {
+    let (range, format) = match query.parse() {
+        Ok(parsed) => parsed,
+        Err(error) => {
+            return ApiError::from(error)
+                .into();
+        }
+    };
+
+    match query.values(&range, &format, &frame_bytes!()) {
+        Ok(values) => TimedResponse::new(values, key.into())
+            .into(),
+        Err(error) => ApiError::from(error)
+            .into()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+struct BigIntQuery {
+    bits: u64,
+    format: Option<String>
+}
+
+#[utoipa::path(
+    summary = "Random integer of up to the tier's bit limit",
+    params(BigIntQuery),
+    responses(
+        (status = 200, body = TimedResponse<String>),
+        (status = 400, body = ErrorEnvelope),
+        (status = 503, body = ErrorEnvelope)
+    ),
+    security((), ("api_key" = []))
+)]
+#[get("/bigint")]
+pub async fn random_bigint(query: Query<BigIntQuery>, key: MaybeApiKey) -> impl Responder {
+    let parsed = check_bits(query.bits, 1, key.tier().max_bigint_bits())
+        .and_then(|bits| Ok((bits, BigIntFormat::parse(query.format.as_deref())?)));
+
+    let (bits, format) = match parsed {
+        Ok(parsed) => parsed,
+        Err(error) => return ApiError::from(error).into()
+    };
+
+    let bytes = frame_bytes!();
+
+    let value = cancellable(
+        move |_| Some(format.format(&random_bits(&bytes, bits))),
+        Duration::from_secs(30)
+    )
+        .await;
+
+    match value {
+        Ok(value) => TimedResponse::new(value, key.into())
+            .into(),
+        Err(error) => ApiError::from(error)
+            .into()
+    }
+}
+
+#[utoipa::path(
+    summary = "Random prime of up to the tier's bit limit",
+    params(BigIntQuery),
+    responses(
+        (status = 200, body = TimedResponse<String>),
+        (status = 400, body = ErrorEnvelope),
+        (status = 503, body = ErrorEnvelope)
+    ),
+    security((), ("api_key" = []))
+)]
+#[get("/prime")]
+pub async fn random_prime_number(query: Query<BigIntQuery>, key: MaybeApiKey) -> impl Responder {
+    let parsed = check_bits(query.bits, 2, key.tier().max_prime_bits())
+        .and_then(|bits| Ok((bits, BigIntFormat::parse(query.format.as_deref())?)));
+
+    let (bits, format) = match parsed {
+        Ok(parsed) => parsed,
+        Err(error) => return ApiError::from(error).into()
+    };
+
+    let bytes = frame_bytes!();
+
+    let value = cancellable(
+        move |cancelled| random_prime(&bytes, bits, cancelled)
+            .map(|prime| format.format(&prime)),
+        Duration::from_secs(30)
+    )
+        .await;
+
+    match value {
+        Ok(value) => TimedResponse::new(value, key.into())
+            .into(),
+        Err(error) => ApiError::from(error)
+            .into()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+struct SeedQuery {
+    algorithm: Option<String>,
+    outputs: Option<usize>
+}
+
+impl SeedQuery {
+    fn algorithm(&self) -> Result<SeedAlgorithm, SeedError> {
+        self.algorithm
+            .as_deref()
+            .unwrap_or("chacha20")
+            .parse()
+    }
+}
+
+// seeds always get a receipt so they can be replayed later.
+#[utoipa::path(
+    summary = "Seed for a reproducible PRNG, always issued with a receipt",
+    params(SeedQuery),
+    responses(
+        (status = 200, body = TimedResponse<Seed>),
+        (status = 400, body = ErrorEnvelope)
+    ),
+    security((), ("api_key" = []))
+)]
+#[get("/seed")]
+pub async fn random_seed(query: Query<SeedQuery>, key: MaybeApiKey) -> impl Responder {
+    let bytes = frame_bytes!();
+
+    let seed = query
+        .algorithm()
+        .and_then(|algorithm| algorithm.seed(&bytes, query.outputs));
+
+    match seed {
+        Ok(seed) => TimedResponse::new(seed, key.into())
+            .require_receipt()
+            .into(),
+        Err(error) => ApiError::from(error)
+            .into()
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+struct ChoiceQuery {
+    count: Option<usize>,
+    replacement: Option<bool>
+}
+
+// a single pick is returned as is, while `count` always returns a list.
+#[utoipa::path(
+    summary = "Weighted random choice, a list of picks when count is set",
+    params(ChoiceQuery),
+    responses(
+        (status = 200, body = TimedResponse<Value>),
+        (status = 400, body = ErrorEnvelope)
+    ),
+    security((), ("api_key" = []))
+)]
+#[post("/choice")]
+pub async fn random_choice(query: Query<ChoiceQuery>, items: Json<Vec<WeightedItem>>, key: MaybeApiKey) -> impl Responder {
+    record_body(&*items);
+
+    let bytes = frame_bytes!();
+
+    let picks = choose(
+        &bytes,
+        items.into_inner(),
+        query.count.unwrap_or(1),
+        query.replacement.unwrap_or(true)
+    );
+
+    match (picks, query.count) {
+        (Ok(picks), Some(_)) => TimedResponse::new(picks, key.into())
+            .into(),
+        (Ok(mut picks), None) => TimedResponse::new(picks.remove(0), key.into())
+            .into(),
+        (Err(error), _) => ApiError::from(error)
+            .into()
+    }
+}
+
+const MAX_OPERATIONS: usize = 32;
+
+#[derive(Deserialize, Serialize, ToSchema)]
+struct BatchOperation {
+    #[serde(rename = "type")]
+    #[schema(example = "color")]
+    kind: String,
+    // the parameters of the matching endpoint, with the items of a choice under `items`.
+    #[serde(default)]
+    params: Value
+}
+
+#[derive(Serialize, ToSchema)]
+struct BatchResult<'r> {
+    status: u16,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<&'r Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorBody<'r>>
+}
+
+#[derive(Deserialize)]
+struct ChoiceParams {
+    items: Vec<WeightedItem>,
+    #[serde(flatten)]
+    query: ChoiceQuery
+}
+
+impl BatchOperation {
+    fn params<T: DeserializeOwned>(&self) -> Result<T, ApiError> {
+        let params = match &self.params {
+            Value::Null => json!({}),
+            params => params.clone()
+        };
+
+        from_value(params)
+            .map_err(|error| ApiError::bad_request("invalid_params", error))
+    }
+
+    // the operation is validated before it's charged, so invalid ones
+    // don't take from the key's allowance.
+    async fn run(&self, key: &ApiKey, bytes: &Bytes) -> Result<Value, ApiError> {
+        Ok(match self.kind.as_str() {
+            "unsigned" => {
+                key.consume(32)?;
+                json!(get_unsigned(bytes))
+            },
+            "signed" => {
+                key.consume(32)?;
+                json!(get_signed(bytes))
+            },
+            "boolean" => {
+                key.consume(1)?;
+                json!(get_bool(bytes))
+            },
+            "uuid" => {
+                key.consume(122)?;
+                json!(get_uuid(bytes))
+            },
+            "color" => {
+                let query = self.params::<ColorQuery>()?;
+                let format = query.format()?;
+
+                query.background()?;
+                key.consume(query.bits())?;
+                json!(query.constrain(query.color(bytes))?.formatted(format))
+            },
+            "palette" => {
+                let palette = self.params::<PaletteQuery>()?;
+                let color = self.params::<ColorQuery>()?;
+                let scheme = palette.scheme()?;
+                let format = color.format()?;
+
+                color.background()?;
+                key.consume(color.bits())?;
+
+                let values = color
+                    .color(bytes)
+                    .palette(scheme, palette.size(scheme))
+                    .into_iter()
+                    .map(|value| Ok(color.constrain(value)?.formatted(format)))
+                    .collect::<Result<Vec<_>, ColorError>>()?;
+
+                json!(values)
+            },
+            "dice" => {
+                let expression = self.params::<DiceQuery>()?
+                    .roll
+                    .parse::<DiceExpression>()?;
+
+                key.consume(expression.dice() as u64 * 32)?;
+                json!(expression.roll(bytes))
+            },
+            "geo" => {
+                let query = self.params::<GeoQuery>()?;
+                let (area, geojson) = query.parse()?;
+
+                key.consume(query.count() as u64 * 64)?;
+                query.points(&area, geojson, bytes)
+            },
+            "datetime" => {
+                let query = self.params::<DateTimeQuery>()?;
+                let (range, format) = query.parse()?;
+
+                range.check_format(&format)?;
+                key.consume(query.count() as u64 * 64)?;
+                query.values(&range, &format, bytes)?
+            },
+            "seed" => {
+                let query = self.params::<SeedQuery>()?;
+                let algorithm = query.algorithm()?;
+
+                check_outputs(query.outputs)?;
+                key.consume(256)?;
+                json!(algorithm.seed(bytes, query.outputs)?)
+            },
+            "bigint" => {
+                let query = self.params::<BigIntQuery>()?;
+                let bits = check_bits(query.bits, 1, key.tier().max_bigint_bits())?;
+                let format = BigIntFormat::parse(query.format.as_deref())?;
+                let bytes = bytes.clone();
+
+                key.consume(bits)?;
+
+                let value = cancellable(
+                    move |_| Some(format.format(&random_bits(&bytes, bits))),
+                    Duration::from_secs(30)
+                )
+                    .await?;
+
+                json!(value)
+            },
+            "choice" => {
+                let ChoiceParams { items, query } = self.params()?;
+                let count = query.count.unwrap_or(1);
+                let replacement = query.replacement.unwrap_or(true);
+
+                check_choice(&items, count, replacement)?;
+
+                // the count is bounded once checked, the product can't overflow.
+                key.consume(count as u64 * 64)?;
+
+                let mut picks = choose(bytes, items, count, replacement)?;
+
+                match query.count {
+                    Some(_) => json!(picks),
+                    None => picks.remove(0)
+                }
+            },
+            kind => return Err(ApiError::bad_request(
+                "invalid_type",
+                format!("Invalid type `{kind}`, expected one of unsigned, signed, boolean, uuid, color, palette, dice, geo, datetime, seed, bigint or choice.")
+            ))
+        })
+    }
+}
+
+impl<'r> From<&'r Result<Value, ApiError>> for BatchResult<'r> {
+    fn from(result: &'r Result<Value, ApiError>) -> Self {
+        match result {
+            Ok(value) => Self {
+                status: StatusCode::OK.as_u16(),
+                value: Some(value),
+                error: None
+            },
+            Err(error) => Self {
+                status: error.status_code().as_u16(),
+                value: None,
+                error: Some(error.body())
+            }
         }
     }
 }
+
+// each operation fills its own slot, in order, so one failing doesn't fail
+// the whole batch.
+#[utoipa::path(
+    summary = "Run several generators in one call, each operation counts against the key's allowances",
+    request_body = Vec<BatchOperation>,
+    responses(
+        (status = 200, body = TimedResponse<Vec<BatchResult>>),
+        (status = 400, body = ErrorEnvelope),
+        (status = 401, body = ErrorEnvelope)
+    ),
+    security(("api_key" = []))
+)]
+#[post("/batch")]
+pub async fn random_batch(operations: Json<Vec<BatchOperation>>, key: MaybeApiKey) -> impl Responder {
+    let key = require_key!(key, "An api key is required to run batches.");
+
+    record_body(&*operations);
+
+    if !(1..=MAX_OPERATIONS).contains(&operations.len()) {
+        return ApiError::bad_request(
+            "invalid_batch",
+            format!("A batch takes between 1 and {MAX_OPERATIONS} operations.")
+        )
+            .into();
+    }
+
+    let bytes = frame_bytes!();
+
+    let mut results = Vec::with_capacity(operations.len());
+
+    for operation in operations.iter() {
+        results.push(operation.run(&key, &bytes).await);
+    }
+
+    // seeds always get a receipt, as they do on their own.
+    let seeded = operations
+        .iter()
+        .zip(&results)
+        .any(|(operation, result)| operation.kind == "seed" && result.is_ok());
+
+    let response = TimedResponse::new(
+        results
+            .iter()
+            .map(BatchResult::from)
+            .collect::<Vec<_>>(),
+        Some(key)
+    );
+
+    match seeded {
+        true => response.require_receipt().into(),
+        false => response.into()
+    }
+}